@@ -0,0 +1,83 @@
+//! Compares reading and writing primitive arrays as a whole with doing it element by element.
+//! Run with `cargo bench --bench arrays`.
+
+use std::hint::black_box;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use structurs::{Endian, PrimitiveRead, PrimitiveWrite, Read, Write};
+
+const ITERATIONS: u32 = 10_000;
+
+/// Runs `f` many times and prints the average time it took.
+fn bench<F>(name: &str, mut f: F)
+where
+  F: FnMut(),
+{
+  let start = Instant::now();
+  for _ in 0..ITERATIONS {
+    f();
+  }
+  let elapsed: Duration = start.elapsed() / ITERATIONS;
+  println!("{:<32} {:>10?}", name, elapsed);
+}
+
+fn main()
+{
+  let bytes = vec![0xA5u8; 4096];
+
+  bench("read [u8; 4096]", || {
+    let mut c = Cursor::new(&bytes[..]);
+    black_box(<[u8; 4096]>::read(&mut c).unwrap());
+  });
+  bench("read 4096 u8 one by one", || {
+    let mut c = Cursor::new(&bytes[..]);
+    let mut array = [0u8; 4096];
+    for value in array.iter_mut() {
+      *value = u8::read(&mut c).unwrap();
+    }
+    black_box(array);
+  });
+  bench("read [u32; 1024] big-endian", || {
+    let mut c = Cursor::new(&bytes[..]);
+    black_box(<[u32; 1024]>::read_endian(&mut c, Endian::Big).unwrap());
+  });
+  bench("read 1024 u32 one by one", || {
+    let mut c = Cursor::new(&bytes[..]);
+    let mut array = [0u32; 1024];
+    for value in array.iter_mut() {
+      *value = u32::read_be(&mut c).unwrap();
+    }
+    black_box(array);
+  });
+
+  // Readers that aren't in memory have a cost for every call, like a virtual call here.
+  bench("read [u32; 1024] from dyn Read", || {
+    let mut c = Cursor::new(&bytes[..]);
+    let reader: &mut dyn std::io::Read = black_box(&mut c);
+    black_box(<[u32; 1024]>::read_endian(&mut &mut *reader, Endian::Big).unwrap());
+  });
+  bench("read 1024 u32 from dyn Read", || {
+    let mut c = Cursor::new(&bytes[..]);
+    let reader: &mut dyn std::io::Read = black_box(&mut c);
+    let mut array = [0u32; 1024];
+    for value in array.iter_mut() {
+      *value = u32::read_be(&mut &mut *reader).unwrap();
+    }
+    black_box(array);
+  });
+
+  let words = [0xA5A5A5A5u32; 1024];
+  bench("write [u32; 1024] big-endian", || {
+    let mut out = Vec::with_capacity(4096);
+    words.write_endian(&mut out, Endian::Big).unwrap();
+    black_box(out);
+  });
+  bench("write 1024 u32 one by one", || {
+    let mut out = Vec::with_capacity(4096);
+    for word in &words {
+      word.write_be(&mut out).unwrap();
+    }
+    black_box(out);
+  });
+}
@@ -0,0 +1,274 @@
+//! Traits for reading and writing data types asynchronously with tokio. These are enabled by the
+//! `tokio` feature.
+
+use std::convert::TryFrom;
+use std::future::Future;
+use std::io;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::{char_from_u32, Endian};
+
+/// This trait can be used to read data types from an asynchronous source like a socket. It can be
+/// derived for structs with `#[derive(structurs::AsyncRead)]`, in which case each field is read
+/// one after another and awaited. The attributes of `structurs::Read` can be used with it, except
+/// for the ones that need blocking IO: `cstring`, `terminator`, `pad(align)`, `pad_before(align)`,
+/// `pad_after(align)`, `fixed`, `offset`, `align`, `read_with`, `write_with`, `varint`, `crc32` and
+/// `digest`. `AsyncWrite` can't be derived with them either.
+///
+/// ```
+/// use structurs::{AsyncRead, AsyncWrite};
+///
+/// #[derive(structurs::AsyncRead, structurs::AsyncWrite, Debug, PartialEq)]
+/// #[be]
+/// struct Packet
+/// {
+///   kind: u8,
+///   #[length_prefix(u16)]
+///   payload: Vec<u16>,
+///   #[le]
+///   checksum: u32,
+/// }
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main()
+/// {
+///   let (mut client, mut server) = tokio::io::duplex(64);
+///   let packet = Packet { kind: 1, payload: vec![2, 3], checksum: 0xDEADBEEF };
+///   packet.write_async(&mut client).await.unwrap();
+///   assert_eq!(packet, Packet::read_async(&mut server).await.unwrap());
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(structurs::AsyncRead)]
+/// struct Test
+/// {
+///   // error: 'cstring' attribute is not supported by the async derive macros.
+///   #[cstring]
+///   name: String,
+/// }
+/// ```
+pub trait AsyncRead: Sized + Send
+{
+  fn read_async<R>(reader: &mut R) -> impl Future<Output = io::Result<Self>> + Send
+  where
+    R: tokio::io::AsyncRead + Unpin + Send;
+
+  /// Reads the data type from a source using `endian` for the parts of it that don't have a fixed
+  /// endian. See [`structurs::Read::read_endian`].
+  fn read_async_endian<R>(reader: &mut R, endian: Endian) -> impl Future<Output = io::Result<Self>> + Send
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    let _ = endian;
+    Self::read_async(reader)
+  }
+}
+
+/// This trait can be used to write data types to an asynchronous sink like a socket. It can be
+/// derived for structs with `#[derive(structurs::AsyncWrite)]`. See [`structurs::AsyncRead`].
+pub trait AsyncWrite: Sync
+{
+  fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send;
+
+  /// Writes the data type to a sink using `endian` for the parts of it that don't have a fixed
+  /// endian. See [`structurs::Write::write_endian`].
+  fn write_async_endian<W>(&self, writer: &mut W, endian: Endian) -> impl Future<Output = io::Result<()>> + Send
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    let _ = endian;
+    self.write_async(writer)
+  }
+}
+
+macro_rules! impl_primitive_async {
+  ($ty:ty, $bytes:expr) => {
+    impl AsyncRead for $ty
+    {
+      fn read_async<R>(reader: &mut R) -> impl Future<Output = io::Result<Self>> + Send
+      where
+        R: tokio::io::AsyncRead + Unpin + Send,
+      {
+        Self::read_async_endian(reader, Endian::Native)
+      }
+
+      fn read_async_endian<R>(reader: &mut R, endian: Endian) -> impl Future<Output = io::Result<Self>> + Send
+      where
+        R: tokio::io::AsyncRead + Unpin + Send,
+      {
+        async move {
+          let mut buf: [u8; $bytes] = [0; $bytes];
+          reader.read_exact(&mut buf).await?;
+          Ok(match endian {
+            Endian::Little => <$ty>::from_le_bytes(buf),
+            Endian::Big => <$ty>::from_be_bytes(buf),
+            Endian::Native => <$ty>::from_ne_bytes(buf),
+          })
+        }
+      }
+    }
+
+    impl AsyncWrite for $ty
+    {
+      fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+      where
+        W: tokio::io::AsyncWrite + Unpin + Send,
+      {
+        self.write_async_endian(writer, Endian::Native)
+      }
+
+      fn write_async_endian<W>(&self, writer: &mut W, endian: Endian) -> impl Future<Output = io::Result<()>> + Send
+      where
+        W: tokio::io::AsyncWrite + Unpin + Send,
+      {
+        let bytes = match endian {
+          Endian::Little => self.to_le_bytes(),
+          Endian::Big => self.to_be_bytes(),
+          Endian::Native => self.to_ne_bytes(),
+        };
+        async move { writer.write_all(&bytes).await }
+      }
+    }
+  };
+}
+
+impl_primitive_async!(u8, 1);
+impl_primitive_async!(u16, 2);
+impl_primitive_async!(u32, 4);
+impl_primitive_async!(u64, 8);
+impl_primitive_async!(u128, 16);
+impl_primitive_async!(i8, 1);
+impl_primitive_async!(i16, 2);
+impl_primitive_async!(i32, 4);
+impl_primitive_async!(i64, 8);
+impl_primitive_async!(i128, 16);
+impl_primitive_async!(f32, 4);
+impl_primitive_async!(f64, 8);
+
+impl AsyncRead for bool
+{
+  async fn read_async<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    Ok(u8::read_async(reader).await? != 0)
+  }
+}
+
+impl AsyncWrite for bool
+{
+  fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    let byte = *self as u8;
+    async move { byte.write_async(writer).await }
+  }
+}
+
+impl AsyncRead for ()
+{
+  async fn read_async<R>(_reader: &mut R) -> io::Result<Self>
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    Ok(())
+  }
+}
+
+impl AsyncWrite for ()
+{
+  async fn write_async<W>(&self, _writer: &mut W) -> io::Result<()>
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    Ok(())
+  }
+}
+
+impl AsyncRead for char
+{
+  fn read_async<R>(reader: &mut R) -> impl Future<Output = io::Result<Self>> + Send
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    Self::read_async_endian(reader, Endian::Native)
+  }
+
+  async fn read_async_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    char_from_u32(u32::read_async_endian(reader, endian).await?)
+  }
+}
+
+impl AsyncWrite for char
+{
+  fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    self.write_async_endian(writer, Endian::Native)
+  }
+
+  fn write_async_endian<W>(&self, writer: &mut W, endian: Endian) -> impl Future<Output = io::Result<()>> + Send
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    let value = *self as u32;
+    async move { value.write_async_endian(writer, endian).await }
+  }
+}
+
+impl<T, const N: usize> AsyncRead for [T; N]
+where
+  T: AsyncRead,
+{
+  fn read_async<R>(reader: &mut R) -> impl Future<Output = io::Result<Self>> + Send
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    Self::read_async_endian(reader, Endian::Native)
+  }
+
+  async fn read_async_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: tokio::io::AsyncRead + Unpin + Send,
+  {
+    let mut elems = Vec::with_capacity(N);
+    for _ in 0..N {
+      elems.push(T::read_async_endian(reader, endian).await?);
+    }
+    match <[T; N]>::try_from(elems) {
+      Ok(array) => Ok(array),
+      Err(_) => unreachable!("exactly N elements were read"),
+    }
+  }
+}
+
+impl<T, const N: usize> AsyncWrite for [T; N]
+where
+  T: AsyncWrite,
+{
+  fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    self.write_async_endian(writer, Endian::Native)
+  }
+
+  async fn write_async_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: tokio::io::AsyncWrite + Unpin + Send,
+  {
+    for elem in self.iter() {
+      elem.write_async_endian(writer, endian).await?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,81 @@
+use alloc::borrow::Cow;
+
+use crate::{io, Write};
+
+/// `Cow<[u8]>` is written as its bytes, whether it is borrowed or owned. Fields of this type can
+/// have a `#[length_prefix]` or a `#[count]` like `Vec<u8>` fields, in which case they are read
+/// into the owned variant.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use std::io::Cursor;
+/// use structurs::{Reader, Write, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Entry<'a>
+/// {
+///   #[length_prefix(u8)]
+///   name: Cow<'a, str>,
+///   #[be]
+///   #[length_prefix(u16)]
+///   data: Cow<'a, [u8]>,
+/// }
+///
+/// let borrowed = Entry {
+///   name: Cow::Borrowed("id"),
+///   data: Cow::Borrowed(&[1, 2, 3]),
+/// };
+/// let owned = Entry {
+///   name: Cow::Owned(String::from("id")),
+///   data: Cow::Owned(vec![1, 2, 3]),
+/// };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&borrowed).unwrap();
+/// assert_eq!(b"\x02id\x00\x03\x01\x02\x03", &c.get_ref()[..]);
+/// assert_eq!(*c.get_ref(), owned.to_vec().unwrap());
+///
+/// c.set_position(0);
+/// let entry = c.read_as::<Entry>().unwrap();
+/// assert_eq!(borrowed, entry);
+/// assert!(matches!(entry.name, Cow::Owned(_)));
+/// assert!(matches!(entry.data, Cow::Owned(_)));
+///
+/// let bytes: Cow<[u8]> = Cow::Borrowed(b"raw");
+/// assert_eq!(b"raw", &Write::to_vec(&bytes).unwrap()[..]);
+/// ```
+impl Write for Cow<'_, [u8]>
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    writer.write_all(self)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    self.len()
+  }
+}
+
+/// `Cow<str>` is written as its UTF-8 bytes, whether it is borrowed or owned. Like `Cow<[u8]>`,
+/// fields of this type can have a `#[length_prefix]` or a `#[count]`, in which case they are read
+/// into the owned variant.
+impl Write for Cow<'_, str>
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    writer.write_all(self.as_bytes())
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    self.len()
+  }
+}
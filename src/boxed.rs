@@ -0,0 +1,94 @@
+use alloc::boxed::Box;
+
+use crate::{io, ByteSize, Endian, Read, Write};
+
+/// `Box<T>` is read and written as the `T` it contains, e.g. to keep large nested structs off the
+/// stack.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Header
+/// {
+///   magic: [u8; 4],
+///   entries: u16,
+/// }
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct File
+/// {
+///   #[be]
+///   header: Box<Header>,
+///   version: u8,
+/// }
+///
+/// let file = File {
+///   header: Box::new(Header {
+///     magic: *b"FILE",
+///     entries: 3,
+///   }),
+///   version: 1,
+/// };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&file).unwrap();
+/// assert_eq!(b"FILE\0\x03\x01".to_vec(), *c.get_ref());
+///
+/// c.set_position(0);
+/// assert_eq!(file, c.read_as::<File>().unwrap());
+/// ```
+impl<T> Read for Box<T>
+where
+  T: Read,
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(Box::new(T::read(reader)?))
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(Box::new(T::read_endian(reader, endian)?))
+  }
+}
+
+impl<T> Write for Box<T>
+where
+  T: Write,
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    (**self).write(writer)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    (**self).write_endian(writer, endian)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    (**self).size_hint()
+  }
+}
+
+impl<T> ByteSize for Box<T>
+where
+  T: ByteSize,
+{
+  const SIZE: usize = T::SIZE;
+}
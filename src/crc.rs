@@ -0,0 +1,195 @@
+//! CRC32 of the bytes that are read or written through a reader or writer, for `#[crc32]` fields.
+
+use crate::io;
+use crate::{CountingReader, CountingWriter};
+
+/// Lookup table of the CRC32 (IEEE 802.3) polynomial in its reflected form.
+const TABLE: [u32; 256] = {
+  let mut table = [0u32; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+      bit += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+};
+
+/// CRC32 that is updated with the bytes as they come.
+#[derive(Clone, Copy)]
+pub struct Crc32
+{
+  state: u32,
+}
+
+impl Crc32
+{
+  pub fn new() -> Self
+  {
+    Self { state: !0 }
+  }
+
+  pub fn update(&mut self, bytes: &[u8])
+  {
+    for &byte in bytes {
+      self.state = TABLE[((self.state ^ byte as u32) & 0xff) as usize] ^ (self.state >> 8);
+    }
+  }
+
+  /// Returns the CRC32 of the bytes so far.
+  pub fn value(&self) -> u32
+  {
+    !self.state
+  }
+}
+
+impl Default for Crc32
+{
+  fn default() -> Self
+  {
+    Self::new()
+  }
+}
+
+/// Readers and writers that know the CRC32 of the bytes that went through them.
+pub trait Checksum
+{
+  fn crc32(&self) -> u32;
+}
+
+impl<T> Checksum for &mut T
+where
+  T: Checksum,
+{
+  fn crc32(&self) -> u32
+  {
+    (**self).crc32()
+  }
+}
+
+impl<R> Checksum for CountingReader<R>
+where
+  R: Checksum,
+{
+  fn crc32(&self) -> u32
+  {
+    self.get_ref().crc32()
+  }
+}
+
+impl<W> Checksum for CountingWriter<W>
+where
+  W: Checksum,
+{
+  fn crc32(&self) -> u32
+  {
+    self.get_ref().crc32()
+  }
+}
+
+/// Reader that computes the CRC32 of the bytes that are read through it.
+pub struct Crc32Reader<R>
+{
+  inner: R,
+  crc: Crc32,
+}
+
+impl<R> Crc32Reader<R>
+{
+  pub fn new(inner: R) -> Self
+  {
+    Self {
+      inner,
+      crc: Crc32::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &R
+  {
+    &self.inner
+  }
+}
+
+impl<R> Checksum for Crc32Reader<R>
+{
+  fn crc32(&self) -> u32
+  {
+    self.crc.value()
+  }
+}
+
+impl<R> io::Read for Crc32Reader<R>
+where
+  R: io::Read,
+{
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+  {
+    let read = self.inner.read(buf)?;
+    self.crc.update(&buf[..read]);
+    Ok(read)
+  }
+}
+
+/// Seeking doesn't change the CRC32, only the bytes that are read after it are added to it.
+impl<S> io::Seek for Crc32Reader<S>
+where
+  S: io::Seek,
+{
+  fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64>
+  {
+    self.inner.seek(pos)
+  }
+}
+
+/// Writer that computes the CRC32 of the bytes that are written through it.
+pub struct Crc32Writer<W>
+{
+  inner: W,
+  crc: Crc32,
+}
+
+impl<W> Crc32Writer<W>
+{
+  pub fn new(inner: W) -> Self
+  {
+    Self {
+      inner,
+      crc: Crc32::new(),
+    }
+  }
+
+  pub fn get_ref(&self) -> &W
+  {
+    &self.inner
+  }
+}
+
+impl<W> Checksum for Crc32Writer<W>
+{
+  fn crc32(&self) -> u32
+  {
+    self.crc.value()
+  }
+}
+
+impl<W> io::Write for Crc32Writer<W>
+where
+  W: io::Write,
+{
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+  {
+    let written = self.inner.write(buf)?;
+    self.crc.update(&buf[..written]);
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()>
+  {
+    self.inner.flush()
+  }
+}
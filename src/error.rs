@@ -0,0 +1,183 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::io;
+
+/// Error that tells which field of a struct failed to be read and where it starts. The derived
+/// [`structurs::Read`] implementations return these inside of the [`io::Error`]s of the fields
+/// that fail, and they can be taken back out of them with `structurs::Error::from`. Errors of
+/// nested structs name the whole path to the field, e.g. `header.version`, and their offset is
+/// counted from the start of the outermost struct.
+///
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use structurs::Reader;
+///
+/// #[derive(structurs::Read, Debug)]
+/// struct Header
+/// {
+///   magic: u32,
+///   version: u16,
+/// }
+///
+/// #[derive(structurs::Read, Debug)]
+/// struct File
+/// {
+///   size: u16,
+///   header: Header,
+/// }
+///
+/// // The input ends in the middle of the version.
+/// let err = Cursor::new(vec![0; 7]).read_as::<File>().unwrap_err();
+/// assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+///
+/// let err = structurs::Error::from(err);
+/// assert_eq!(Some("header.version"), err.field());
+/// assert_eq!(Some(6), err.offset());
+/// assert_eq!(
+///   "failed to read field 'header.version' at byte 6: failed to fill whole buffer",
+///   err.to_string()
+/// );
+///
+/// // The input ends right before the header, so none of its bytes are there.
+/// let err = structurs::Error::from(Cursor::new(vec![0; 2]).read_as::<File>().unwrap_err());
+/// assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+/// assert_eq!(Some("header.magic"), err.field());
+/// assert_eq!(Some(2), err.offset());
+/// ```
+#[derive(Debug)]
+pub struct Error
+{
+  field: Option<String>,
+  offset: Option<u64>,
+  /// Number of bytes that were read from the start of the field until it failed. Used to find the
+  /// offset of the field in the structs that contain it.
+  read: u64,
+  source: io::Error,
+}
+
+impl Error
+{
+  /// Returns the path of the field that failed to be read.
+  pub fn field(&self) -> Option<&str>
+  {
+    self.field.as_deref()
+  }
+
+  /// Returns the offset in bytes of the field that failed to be read from the start of the
+  /// outermost struct. It is `None` when the offset isn't known, e.g. when reading asynchronously.
+  pub fn offset(&self) -> Option<u64>
+  {
+    self.offset
+  }
+
+  pub fn kind(&self) -> io::ErrorKind
+  {
+    self.source.kind()
+  }
+
+  /// Returns the underlying error without the context of the field.
+  pub fn into_inner(self) -> io::Error
+  {
+    self.source
+  }
+
+  /// Adds the context of the field `field` of a struct to an error of reading it. `position` is the
+  /// number of bytes that were read from the struct before the field and when it failed, if they
+  /// are known.
+  pub(crate) fn in_field(field: &str, position: Option<(u64, u64)>, err: io::Error) -> io::Error
+  {
+    let kind = err.kind();
+    let error = match Error::from(err) {
+      // The field of a nested struct started as many bytes before the end as were read from it.
+      Error {
+        field: Some(inner),
+        offset,
+        read,
+        source,
+      } => Error {
+        field: Some(format!("{}.{}", field, inner)),
+        offset: match (position, offset) {
+          (Some((_, end)), Some(_)) => Some(end - read),
+          _ => None,
+        },
+        read,
+        source,
+      },
+      Error { source, .. } => Error {
+        field: Some(String::from(field)),
+        offset: position.map(|(start, _)| start),
+        read: position.map_or(0, |(start, end)| end - start),
+        source,
+      },
+    };
+    io::Error::new(kind, error)
+  }
+}
+
+impl From<io::Error> for Error
+{
+  #[cfg(feature = "std")]
+  fn from(err: io::Error) -> Self
+  {
+    if err.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+      if let Some(Ok(error)) = err.into_inner().map(|inner| inner.downcast::<Error>()) {
+        return *error;
+      }
+      unreachable!("the error was checked to be a structurs::Error");
+    }
+    Error {
+      field: None,
+      offset: None,
+      read: 0,
+      source: err,
+    }
+  }
+
+  /// Without `std` the context of the field is only kept in the message of the error.
+  #[cfg(not(feature = "std"))]
+  fn from(err: io::Error) -> Self
+  {
+    Error {
+      field: None,
+      offset: None,
+      read: 0,
+      source: err,
+    }
+  }
+}
+
+impl From<Error> for io::Error
+{
+  fn from(err: Error) -> Self
+  {
+    match err.field {
+      Some(_) => io::Error::new(err.kind(), err),
+      None => err.source,
+    }
+  }
+}
+
+impl fmt::Display for Error
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+  {
+    match (&self.field, self.offset) {
+      (Some(field), Some(offset)) => {
+        write!(f, "failed to read field '{}' at byte {}: {}", field, offset, self.source)
+      }
+      (Some(field), None) => write!(f, "failed to read field '{}': {}", field, self.source),
+      (None, _) => self.source.fmt(f),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error
+{
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)>
+  {
+    Some(&self.source)
+  }
+}
@@ -0,0 +1,314 @@
+//! Hash of the bytes that are read or written through a reader or writer, for formats that end with
+//! a digest of the bytes before it, like `#[digest(H)]` fields.
+
+use crate::crc::{Crc32Reader, Crc32Writer};
+use crate::io;
+use crate::{CountingReader, CountingWriter};
+
+/// Hashers that can be fed bytes, like the `Update` trait of the `digest` crate.
+pub trait Update
+{
+  fn update(&mut self, data: &[u8]);
+}
+
+/// Hashers whose hash can be taken without consuming them, so they can be the hasher of a
+/// `#[digest(H)]` field.
+pub trait Finish: Update
+{
+  type Output;
+
+  /// Returns the hash of the bytes fed to the hasher so far.
+  fn finish(&self) -> Self::Output;
+}
+
+/// Hashers of the `digest` crate, e.g. `sha2::Sha256`, can be used wherever a hasher is expected.
+#[cfg(feature = "digest")]
+impl<D> Update for D
+where
+  D: digest::Update,
+{
+  fn update(&mut self, data: &[u8])
+  {
+    digest::Update::update(self, data)
+  }
+}
+
+/// The output of a hasher of the `digest` crate converts into a byte array of its size, e.g.
+/// `[u8; 32]` for `sha2::Sha256`, which can be the type of its `#[digest(H)]` field.
+///
+/// ```
+/// # #[cfg(feature = "digest")]
+/// # {
+/// use sha2::{Digest, Sha256};
+/// use structurs::Writer;
+///
+/// #[derive(structurs::Write)]
+/// struct Signed
+/// {
+///   body: [u8; 3],
+///   #[digest(Sha256)]
+///   hash: [u8; 32],
+/// }
+///
+/// let mut bytes = Vec::new();
+/// bytes.write_as(&Signed { body: *b"abc", hash: [0; 32] }).unwrap();
+/// assert_eq!(Sha256::digest(b"abc")[..], bytes[3..]);
+/// # }
+/// ```
+#[cfg(feature = "digest")]
+impl<D> Finish for D
+where
+  D: digest::Update + digest::FixedOutput + Clone,
+{
+  type Output = digest::Output<D>;
+
+  fn finish(&self) -> Self::Output
+  {
+    digest::FixedOutput::finalize_fixed(self.clone())
+  }
+}
+
+/// Readers and writers that hash the bytes that went through them.
+pub trait Hashing
+{
+  type Hasher;
+
+  fn hasher(&self) -> &Self::Hasher;
+}
+
+impl<T> Hashing for &mut T
+where
+  T: Hashing,
+{
+  type Hasher = T::Hasher;
+
+  fn hasher(&self) -> &Self::Hasher
+  {
+    (**self).hasher()
+  }
+}
+
+impl<R> Hashing for CountingReader<R>
+where
+  R: Hashing,
+{
+  type Hasher = R::Hasher;
+
+  fn hasher(&self) -> &Self::Hasher
+  {
+    self.get_ref().hasher()
+  }
+}
+
+impl<W> Hashing for CountingWriter<W>
+where
+  W: Hashing,
+{
+  type Hasher = W::Hasher;
+
+  fn hasher(&self) -> &Self::Hasher
+  {
+    self.get_ref().hasher()
+  }
+}
+
+impl<R> Hashing for Crc32Reader<R>
+where
+  R: Hashing,
+{
+  type Hasher = R::Hasher;
+
+  fn hasher(&self) -> &Self::Hasher
+  {
+    self.get_ref().hasher()
+  }
+}
+
+impl<W> Hashing for Crc32Writer<W>
+where
+  W: Hashing,
+{
+  type Hasher = W::Hasher;
+
+  fn hasher(&self) -> &Self::Hasher
+  {
+    self.get_ref().hasher()
+  }
+}
+
+/// Reader that feeds the bytes that are read through it to a hasher, so the hash of what was read
+/// can be checked against the one that follows it.
+pub struct HashingReader<R, H>
+{
+  inner: R,
+  hasher: H,
+}
+
+impl<R, H> HashingReader<R, H>
+{
+  pub fn new(inner: R, hasher: H) -> Self
+  {
+    Self { inner, hasher }
+  }
+
+  /// Returns the hasher, which has been fed the bytes read so far.
+  pub fn hasher(&self) -> &H
+  {
+    &self.hasher
+  }
+
+  pub fn get_ref(&self) -> &R
+  {
+    &self.inner
+  }
+
+  pub fn get_mut(&mut self) -> &mut R
+  {
+    &mut self.inner
+  }
+
+  /// Returns the reader and the hasher.
+  pub fn into_parts(self) -> (R, H)
+  {
+    (self.inner, self.hasher)
+  }
+}
+
+impl<R, H> Hashing for HashingReader<R, H>
+{
+  type Hasher = H;
+
+  fn hasher(&self) -> &H
+  {
+    &self.hasher
+  }
+}
+
+impl<R, H> io::Read for HashingReader<R, H>
+where
+  R: io::Read,
+  H: Update,
+{
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+  {
+    let read = self.inner.read(buf)?;
+    self.hasher.update(&buf[..read]);
+    Ok(read)
+  }
+}
+
+/// Seeking doesn't change the hash, only the bytes that are read after it are fed to the hasher.
+impl<S, H> io::Seek for HashingReader<S, H>
+where
+  S: io::Seek,
+{
+  fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64>
+  {
+    self.inner.seek(pos)
+  }
+}
+
+/// Writer that feeds the bytes that are written through it to a hasher, so the hash of what was
+/// written can be appended to it without going over the bytes again.
+///
+/// ```
+/// use structurs::{HashingWriter, Update, Writer};
+///
+/// /// 64-bit FNV-1a hash.
+/// struct Fnv(u64);
+///
+/// impl Update for Fnv
+/// {
+///   fn update(&mut self, data: &[u8])
+///   {
+///     for &byte in data {
+///       self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100_0000_01b3);
+///     }
+///   }
+/// }
+///
+/// #[derive(structurs::Write)]
+/// struct Body
+/// {
+///   #[le]
+///   id: u32,
+///   #[length_prefix(u8)]
+///   name: String,
+/// }
+///
+/// let body = Body {
+///   id: 7,
+///   name: "seven".to_string(),
+/// };
+/// let mut writer = HashingWriter::new(Vec::new(), Fnv(0xcbf2_9ce4_8422_2325));
+/// writer.write_as(&body).unwrap();
+/// let (mut bytes, hash) = writer.into_parts();
+/// bytes.write_le(&hash.0).unwrap();
+///
+/// let mut expected = Fnv(0xcbf2_9ce4_8422_2325);
+/// expected.update(&bytes[..10]);
+/// assert_eq!(expected.0.to_le_bytes(), bytes[10..]);
+/// ```
+pub struct HashingWriter<W, H>
+{
+  inner: W,
+  hasher: H,
+}
+
+impl<W, H> HashingWriter<W, H>
+{
+  pub fn new(inner: W, hasher: H) -> Self
+  {
+    Self { inner, hasher }
+  }
+
+  /// Returns the hasher, which has been fed the bytes written so far.
+  pub fn hasher(&self) -> &H
+  {
+    &self.hasher
+  }
+
+  pub fn get_ref(&self) -> &W
+  {
+    &self.inner
+  }
+
+  pub fn get_mut(&mut self) -> &mut W
+  {
+    &mut self.inner
+  }
+
+  /// Returns the writer and the hasher, e.g. to finish the hash and write it after the bytes.
+  pub fn into_parts(self) -> (W, H)
+  {
+    (self.inner, self.hasher)
+  }
+}
+
+impl<W, H> Hashing for HashingWriter<W, H>
+{
+  type Hasher = H;
+
+  fn hasher(&self) -> &H
+  {
+    &self.hasher
+  }
+}
+
+impl<W, H> io::Write for HashingWriter<W, H>
+where
+  W: io::Write,
+  H: Update,
+{
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+  {
+    let written = self.inner.write(buf)?;
+    self.hasher.update(&buf[..written]);
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()>
+  {
+    self.inner.flush()
+  }
+}
@@ -0,0 +1,206 @@
+//! IO traits and errors that the traits of this crate use. With the `std` feature, which is enabled
+//! by default, these are the ones from `std::io`. Without it they are minimal versions of them that
+//! only need `core` and `alloc`, so the crate can be used on targets without `std`.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+#[cfg(not(feature = "std"))]
+mod core_io
+{
+  use alloc::string::{String, ToString};
+  use alloc::vec::Vec;
+  use core::fmt;
+
+  pub type Result<T> = core::result::Result<T, Error>;
+
+  /// Kinds of errors that can happen while reading or writing. These are a subset of the kinds of
+  /// `std::io::ErrorKind`.
+  #[derive(Debug, Eq, PartialEq, Copy, Clone)]
+  #[non_exhaustive]
+  pub enum ErrorKind
+  {
+    /// The data that was read is not valid for the type it is read into.
+    InvalidData,
+    /// The value that is written can't be represented in bytes.
+    InvalidInput,
+    /// The source ended before all of the bytes of a value were read.
+    UnexpectedEof,
+    /// The sink didn't accept all of the bytes of a value.
+    WriteZero,
+    /// The operation was interrupted and can be tried again.
+    Interrupted,
+    /// Any other error.
+    Other,
+  }
+
+  /// Error that is returned when reading or writing fails.
+  #[derive(Debug)]
+  pub struct Error
+  {
+    kind: ErrorKind,
+    message: String,
+  }
+
+  impl Error
+  {
+    pub fn new<M>(kind: ErrorKind, message: M) -> Self
+    where
+      M: fmt::Display,
+    {
+      Self {
+        kind,
+        message: message.to_string(),
+      }
+    }
+
+    pub fn kind(&self) -> ErrorKind
+    {
+      self.kind
+    }
+  }
+
+  impl From<ErrorKind> for Error
+  {
+    fn from(kind: ErrorKind) -> Self
+    {
+      Self {
+        kind,
+        message: String::new(),
+      }
+    }
+  }
+
+  impl fmt::Display for Error
+  {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+      if self.message.is_empty() {
+        write!(f, "{:?}", self.kind)
+      } else {
+        f.write_str(&self.message)
+      }
+    }
+  }
+
+  pub trait Read
+  {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()>
+    {
+      while !buf.is_empty() {
+        match self.read(buf) {
+          Ok(0) => return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer")),
+          Ok(read) => buf = &mut buf[read..],
+          Err(err) if err.kind() == ErrorKind::Interrupted => {}
+          Err(err) => return Err(err),
+        }
+      }
+      Ok(())
+    }
+  }
+
+  impl<R> Read for &mut R
+  where
+    R: Read + ?Sized,
+  {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>
+    {
+      (**self).read(buf)
+    }
+  }
+
+  impl Read for &[u8]
+  {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>
+    {
+      let read = buf.len().min(self.len());
+      let (bytes, rest) = self.split_at(read);
+      buf[..read].copy_from_slice(bytes);
+      *self = rest;
+      Ok(read)
+    }
+  }
+
+  pub trait Write
+  {
+    fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+    fn flush(&mut self) -> Result<()>;
+
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()>
+    {
+      while !buf.is_empty() {
+        match self.write(buf) {
+          Ok(0) => return Err(Error::new(ErrorKind::WriteZero, "failed to write whole buffer")),
+          Ok(written) => buf = &buf[written..],
+          Err(err) if err.kind() == ErrorKind::Interrupted => {}
+          Err(err) => return Err(err),
+        }
+      }
+      Ok(())
+    }
+  }
+
+  impl<W> Write for &mut W
+  where
+    W: Write + ?Sized,
+  {
+    fn write(&mut self, buf: &[u8]) -> Result<usize>
+    {
+      (**self).write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()>
+    {
+      (**self).flush()
+    }
+  }
+
+  impl Write for Vec<u8>
+  {
+    fn write(&mut self, buf: &[u8]) -> Result<usize>
+    {
+      self.extend_from_slice(buf);
+      Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()>
+    {
+      Ok(())
+    }
+  }
+
+  /// Position to seek to, see `std::io::SeekFrom`.
+  #[derive(Debug, Eq, PartialEq, Copy, Clone)]
+  pub enum SeekFrom
+  {
+    Start(u64),
+    End(i64),
+    Current(i64),
+  }
+
+  pub trait Seek
+  {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+
+    fn stream_position(&mut self) -> Result<u64>
+    {
+      self.seek(SeekFrom::Current(0))
+    }
+  }
+
+  impl<S> Seek for &mut S
+  where
+    S: Seek + ?Sized,
+  {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64>
+    {
+      (**self).seek(pos)
+    }
+  }
+}
@@ -6,7 +6,7 @@
 //! # Macro
 //!
 //! This crate provides a macro that lets you automatically implement [`structurs::Read`] trait for
-//! you. You need to enable `derive` feature to use this macro.
+//! you. This macro is provided by the `derive` feature which is enabled by default.
 //!
 //! ```edition2018, ignore
 //! #[derive(structurs::Read)]
@@ -17,68 +17,2624 @@
 //! your structure. It also includes attributes that can change the way some of the fields are
 //! read.
 //!
+//! `#[derive(structurs::Write)]` implements [`structurs::Write`] the same way, and
+//! `#[derive(structurs::ByteSize)]` implements [`structurs::ByteSize`] for structures whose fields
+//! always take the same number of bytes. They all accept the same attributes.
+//!
+//! `#[derive(structurs::ReadWrite)]` implements both [`structurs::Read`] and [`structurs::Write`]
+//! from the same attributes, for structures that are read and written the same way.
+//!
+//! `#[derive(structurs::StructursDefault)]` implements [`Default`] with the values that reading
+//! gives the fields when they aren't read, so the default of a structure is what it is read as from
+//! zero bytes, and padding and skipped fields get their `#[default]` value.
+//!
+//! The attributes are parsed by the `structurs-attributes` crate, which other derive macros can use
+//! to accept the same attributes.
+//!
+//! With the `tokio` feature, `#[derive(structurs::AsyncRead)]` and
+//! `#[derive(structurs::AsyncWrite)]` implement `structurs::AsyncRead` and `structurs::AsyncWrite`,
+//! which read and write the fields asynchronously from tokio's sources and sinks.
+//!
+//! With the `digest` feature, the hashers of the `digest` crate implement [`structurs::Update`] and
+//! [`structurs::Finish`], so they can be used with [`structurs::HashingWriter`] and as the hasher
+//! of `#[digest(H)]` fields.
+//!
+//! The crate and the code generated by its macros only need `core` and `alloc` when the `std`
+//! feature, which is enabled by default, is disabled. The traits then use the minimal IO traits and
+//! error type of [`structurs::io`] instead of the ones of `std::io`.
+//!
+//! When a field of a derived structure fails to be read, the error names the field and the byte
+//! where it starts, and it can be turned into a [`structurs::Error`] to get them.
+//!
 //! By default all fields will be read using [`structurs::Read::read`] function, but you might have
 //! fields that might need to be read in big-endian format. In that case you can mark those fields
 //! with `#[be]` attribute.
 //!
-//! ## Attributes
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::ReadWrite, Debug, PartialEq)]
+//! struct Point
+//! {
+//!   #[be]
+//!   x: i16,
+//!   #[le]
+//!   y: i16,
+//! }
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&Point { x: 1, y: -2 }).unwrap();
+//! assert_eq!(vec![0, 1, 0xFE, 0xFF], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(Point { x: 1, y: -2 }, c.read_as::<Point>().unwrap());
+//! ```
+//!
+//! ## Attributes
+//!
+//! The following is the list of attributes that can be used to mark the fields of structures.
+//! Attributes can also be grouped together in a `structurs` attribute to avoid collisions with the
+//! attributes of other macros, e.g. `#[structurs(be, pad(bytes = 4))]` is the same as `#[be]
+//! #[pad(bytes = 4)]`.
+//!
+//! - `#[le]` or `#[little_endian]`, This denotes that the field is in little-endian format.
+//! - `#[be]` or `#[big_endian]`, This denotes that the field is in big-endian format.
+//! - `#[ne]` or `#[native_endian]`, This denotes that the field is in CPU's native endian format.
+//!   Most CPU's will use little-endian format.
+//! - `#[pad]`, This denotes that the field is a padding and is not important. In this case the
+//!   field will be initialized to its default value using [`Default::default`]. By default the
+//!   length of the field type worth of bytes will be read from the reader. You can also pass a
+//!   `bytes` value to this attribute. `#[pad(bytes = N)]` means that N bytes should be read from
+//!   the reader in which case field type is not important and should be [`structurs::Pad`].
+//!   `#[pad(align = N)]` means that the bytes up to the next multiple of N bytes from the start of
+//!   the stream are padding. Padding is written as zeros unless `fill = B` gives another byte, e.g.
+//!   `#[pad(bytes = 4, fill = 0xFF)]`. The bytes that are read are ignored, unless `strict` is
+//!   given too, in which case reading fails with `InvalidData` if they are not all the fill byte.
+//! - `#[reserved(bytes = N)]`, This denotes that the field is padding that must be zero, like
+//!   `#[pad(bytes = N, strict)]`. It is written as zeros and reading fails with `InvalidData` if
+//!   any of its bytes isn't zero. `align = N` can be used just like with `#[pad]`.
+//! - `#[pad_before(bytes = N)]` and `#[pad_after(bytes = N)]`, These denote that N bytes of padding
+//!   come before or after the field, which is still read and written as usual. The padding is read
+//!   and discarded, and written as zeros. `align = N`, `fill = B` and `strict` can be used just
+//!   like with `#[pad]`.
+//! - `#[skip]`, This denotes that the field is not part of the bytes at all. Nothing is read or
+//!   written for it and it is initialized to its default value using [`Default::default`].
+//!   `PhantomData` fields are always skipped.
+//! - `#[default(expr)]`, This changes the value of a padding or skipped field from its default
+//!   value to `expr`. The fields that are read before this field can be used in the expression by
+//!   their names.
+//! - `#[magic = b"..."]`, This denotes that the field is a `[u8; N]` that must always contain the
+//!   given bytes, like the signature of a file format. Reading fails if the bytes are different and
+//!   the given bytes are written regardless of the value of the field.
+//! - `#[assert_eq = value]`, This denotes that the field must be equal to `value` after it is read,
+//!   otherwise reading fails. Values that are not literals can be given as `#[assert_eq(expr)]`.
+//! - `#[length_prefix(T)]`, This denotes that the field is a `Vec` whose elements are preceded by
+//!   their count. The count is read as type `T` (e.g. `u16`) using the endian of the field, then
+//!   that many elements are read. It can also be used on `String` fields, in which case the length
+//!   is the number of bytes of its UTF-8 encoding. `Cow<[T]>` and `Cow<str>` fields are stored like
+//!   `Vec` and `String` fields and are read into their owned variant. `HashMap<K, V>` and
+//!   `BTreeMap<K, V>` fields are stored as the number of entries followed by the key and the value
+//!   of each entry, in the order that the map iterates over them. The count can have an endian of
+//!   its own, e.g. `#[length_prefix(u32, le)]` on a field whose elements are big-endian.
+//! - `#[count(expr)]`, This denotes that the field is a `Vec` whose number of elements is given by
+//!   `expr`. The fields that are read before this field can be used in the expression by their
+//!   names, e.g. `#[count(entry_count)]`. The count is not written with the elements, it is
+//!   written by the field it comes from.
+//! - `#[count_bytes(expr)]`, This denotes that the field is a `Vec` whose elements take `expr`
+//!   bytes, like `#[count(expr)]` with the number of bytes instead of the number of elements. The
+//!   elements must implement `structurs::ByteSize`, and reading fails with `InvalidData` if `expr`
+//!   isn't a multiple of their size. On a `String` it is the same as `#[count(expr)]`.
+//! - `#[repeat = N]`, This denotes that the field is a `Vec` that always has N elements, like an
+//!   array that is kept on the heap. Constant expressions can be given as `#[repeat(expr)]`.
+//!   Writing fails with `InvalidInput` if the field doesn't have N elements.
+//! - `#[max = N]`, This denotes that the field with a `#[length_prefix(T)]` or `#[count(expr)]`
+//!   can't have more than N elements. Reading a larger length fails with `InvalidData` before
+//!   anything is allocated for the elements, and writing a field with more elements fails with
+//!   `InvalidInput`.
+//! - `#[bits = N]`, This denotes that the field takes N bits of an integer that it shares with the
+//!   fields next to it that also have `#[bits = N]`. The first field takes the most significant
+//!   bits, and the bits of the fields must add up to 8, 16, 32, 64 or 128 bits, which is the
+//!   integer that is read or written. The fields can be unsigned integers or `bool`, and writing a
+//!   value that doesn't fit in its bits fails with `InvalidInput`.
+//! - `#[bit_order(lsb)]`, This denotes that the first field of a group of `#[bits = N]` fields
+//!   takes the least significant bits of their integer instead of the most significant ones. It can
+//!   be used on the first field of a group or on the struct or enum to change all of its groups.
+//!   `#[bit_order(msb)]` is the default.
+//! - `#[cstring]`, This denotes that the field is a `String` or `Vec<u8>` that is terminated by a
+//!   NUL byte like C strings. The terminator is not part of the value and the value can't contain a
+//!   NUL byte when it is written.
+//! - `#[terminator = N]`, This denotes that the field is a `Vec<u8>` or `String` that is terminated
+//!   by the byte N, like `#[cstring]` is terminated by a NUL byte. Reading fails with
+//!   `UnexpectedEof` if the source ends before the terminator.
+//! - `#[fixed = N]`, This denotes that the field is a `String` that is stored in N bytes. Strings
+//!   shorter than N bytes are padded with NUL bytes, which are removed when it is read. On a
+//!   `Vec<u8>` field it denotes that the field always has N bytes, and writing fails with
+//!   `InvalidInput` if it doesn't.
+//! - `#[fixed_point(frac = N)]`, This denotes that the `f32` or `f64` field is stored as a
+//!   fixed-point `i32` whose last N bits come after the point, e.g. Q16.16 with `frac = 16`.
+//!   Another integer can be given before it, e.g. `#[fixed_point(u16, frac = 8)]`. The integer uses
+//!   the endian of the field. The value is rounded to the nearest fixed-point number when it is
+//!   written, and writing a value that doesn't fit in the integer fails with `InvalidInput`.
+//! - `#[present_if(expr)]`, This denotes that the field is an `Option` that is only present if
+//!   `expr` is true or a number other than zero. Like `#[count]`, the expression can use the fields
+//!   that are read before this field. When it is not present the field is `None`, and a `None`
+//!   field writes nothing.
+//! - `#[structurs(if = expr)]`, This denotes that the field is only read and written when `expr` is
+//!   true. Like `#[count]`, the expression can use the fields that are read before this field,
+//!   which must be `Copy` to be used in it. When the condition is false an `Option` field is `None`
+//!   and other fields get their default value. Since `if` is a keyword, it can only be used
+//!   grouped.
+//! - `#[offset(expr)]`, This denotes that the field is read at the position `expr` from the start
+//!   of the source instead of after the previous field. Like `#[count]`, the expression can use the
+//!   fields that are read before this field. The source is seeked back afterwards, so the next
+//!   field is read after the previous field. Structs with such fields implement
+//!   [`structurs::ReadSeek`] instead of `structurs::Read` and can't derive `structurs::Write`.
+//! - `#[align = N]`, This denotes that the field starts at a multiple of N bytes from the start of
+//!   the stream, which is where the outermost struct starts. The bytes before it are skipped when
+//!   it is read and written as zeros. Structs that are the elements of an array align their fields
+//!   from their own start instead.
+//! - `#[read_with(path)]`, This denotes that the field is read by calling `path(reader)`, which
+//!   returns `std::io::Result` of the field type, instead of using the type's `structurs::Read`
+//!   implementation.
+//! - `#[write_with(path)]`, This denotes that the field is written by calling
+//!   `path(&field, writer)`, which returns `std::io::Result<()>`, instead of using the type's
+//!   `structurs::Write` implementation.
+//! - `#[varint]`, This denotes that the integer field is stored as an LEB128 varint. Signed
+//!   integers are zigzag encoded. See [`structurs::Varint`].
+//! - `#[crc32]`, This denotes that the `u32` field is the CRC32 of the bytes of the struct that
+//!   come before it. Reading fails with `InvalidData` if the CRC32 that is read doesn't match, and
+//!   the CRC32 of the written bytes is written regardless of the value of the field.
+//! - `#[digest(H)]`, This denotes that the field is the hash of the bytes of the struct that come
+//!   before it, computed by the hasher `H`, which implements [`structurs::Finish`] and `Default`.
+//!   The output of the hasher must convert into the type of the field. Like `#[crc32]`, reading
+//!   fails with `InvalidData` if the hash that is read doesn't match, and the hash of the written
+//!   bytes is written regardless of the value of the field. All `#[digest]` fields of a struct use
+//!   the same hasher.
+//! - `#[utf16]`, This denotes that the `String` field is stored as UTF-16 code units instead of
+//!   UTF-8 bytes. It is used together with `#[length_prefix(T)]` or `#[count(expr)]`, whose length
+//!   is the number of code units. The code units use the endian of the field. Reading fails with
+//!   `InvalidData` if they are not valid UTF-16.
+//! - `#[rest]`, This denotes that the `Vec<u8>` field is all of the bytes up to the end of the
+//!   source, and is written as just its bytes. It can only be used on the last field.
+//! - `#[endian(expr)]`, This denotes that the field is read and written using the endian that
+//!   `expr` evaluates to, a `structurs::Endian`. Like `#[count]`, the expression can use the fields
+//!   that are read before this field, which must be `Copy` to be used in it. Together with a nested
+//!   struct, this lets a byte order mark decide the endian of the rest of the data.
+//!
+//! The endian attributes can also be used on the struct itself. In that case they set the default
+//! endian of all fields of the struct. Fields can still override it with their own attribute.
+//!
+//! Fields whose type is another struct pass their endian on to it. The fields of the nested struct
+//! that don't have their own endian attribute are read using the endian of the field.
+//!
+//! Enums can be derived as well, whether their variants have no fields, unnamed fields or named
+//! fields. A variant is stored as its tag followed by its fields. The tag is the discriminant of
+//! the variant, and its type is given by `#[tag(T)]` on the enum, e.g. `#[tag(u8)]`, or by an
+//! integer `#[repr]`. Reading a tag that isn't the discriminant of any variant fails with
+//! `InvalidData`. Attributes used on the enum set the defaults of the fields of all of its
+//! variants, and an endian attribute on the enum is the endian of the tag as well.
+//!
+//! `#[size = N]` checks that the struct takes exactly N bytes. The build fails if it doesn't, so a
+//! change to the fields can't silently change the layout of a format that must stay the same. The
+//! size comes from `structurs::ByteSize`, so the struct must implement it, and the build fails as
+//! well for structs that don't have a fixed size. The size of a generic struct is checked for the
+//! types it is used with.
+//!
+//! `#[exact_size]` on a struct that also implements `structurs::ByteSize` checks that reading it
+//! takes exactly `SIZE` bytes, and fails with `InvalidData` if it doesn't. It catches a `Read`
+//! implementation of a field, or a `ByteSize` implementation written by hand, that doesn't agree
+//! with the format.
+//!
+//! ## Example
+//!
+//! ```
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // This field will be read using structurs::Read::read function.
+//!   field_1: i64,
+//!   // This field will be read in little-endian format.
+//!   #[le]
+//!   field_2: i16,
+//!   // This field will not be read. But 8 bytes will be read from the reader and discarded.
+//!   #[pad(bytes = 8)]
+//!   pad_field: structurs::Pad,
+//! }
+//! ```
+//!
+//! The endian attributes have long forms, which can be mixed with the short ones.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[big_endian]
+//! struct Header
+//! {
+//!   magic: u16,
+//!   #[le]
+//!   length: u16,
+//!   #[little_endian]
+//!   flags: u16,
+//!   #[structurs(native_endian)]
+//!   local: u16,
+//!   #[length_prefix(u16, little_endian)]
+//!   data: Vec<u16>,
+//! }
+//!
+//! let header = Header {
+//!   magic: 0x0102,
+//!   length: 0x0304,
+//!   flags: 0x0506,
+//!   local: 0x0708,
+//!   data: vec![0x090a],
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&header).unwrap();
+//! let local = 0x0708u16.to_ne_bytes();
+//! assert_eq!(vec![1, 2, 4, 3, 6, 5, local[0], local[1], 1, 0, 9, 10], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(header, c.read_as::<Header>().unwrap());
+//! ```
+//!
+//! Fields that are left out by `#[cfg]` aren't read or written, and `#[cfg_attr]` can choose the
+//! attributes of a field. Doc comments and other attributes on the fields are ignored.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   /// Kind of the record.
+//!   kind: u8,
+//!   /// Only in the extended format.
+//!   #[cfg(feature = "ext")]
+//!   #[le]
+//!   extension: u32,
+//!   #[cfg_attr(not(feature = "ext"), be)]
+//!   #[cfg_attr(feature = "ext", le)]
+//!   length: u16,
+//! }
+//!
+//! assert_eq!(3, Record::SIZE);
+//! let record = Record { kind: 1, length: 2 };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! assert_eq!(vec![1, 0, 2], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(record, c.read_as::<Record>().unwrap());
+//! ```
+//!
+//! Array fields are read and written element by element, so the attributes apply to each element
+//! of the array.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Table
+//! {
+//!   #[be]
+//!   entries: [u32; 1000],
+//! }
+//!
+//! let bytes: Vec<u8> = (0..1000u32).flat_map(|i| i.to_be_bytes()).collect();
+//! let mut c = Cursor::new(bytes.clone());
+//! let table = c.read_as::<Table>().unwrap();
+//! assert!(table.entries.iter().enumerate().all(|(i, &e)| e == i as u32));
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! out.write_as(&table).unwrap();
+//! assert_eq!(bytes, out.into_inner());
+//! ```
+//!
+//! Every element has the endian of the field, whether the array is read in bulk or, in a struct
+//! that seeks, one element at a time.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Words
+//! {
+//!   #[be]
+//!   words: [u32; 4],
+//! }
+//!
+//! #[derive(structurs::Read)]
+//! struct Indirect
+//! {
+//!   at: u8,
+//!   #[be]
+//!   #[offset(at)]
+//!   words: [u32; 4],
+//! }
+//!
+//! let words = [0x0102_0304, 0x0506_0708, 0x090a_0b0c, 0x0d0e_0f10];
+//! let bytes: Vec<u8> = (1..=16).collect();
+//! assert_eq!(words, Cursor::new(bytes.clone()).read_as::<Words>().unwrap().words);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&Words { words }).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//!
+//! let indirect: Vec<u8> = [1].iter().copied().chain(bytes).collect();
+//! assert_eq!(words, Cursor::new(indirect).read_seek_as::<Indirect>().unwrap().words);
+//! ```
+//!
+//! The length of an array can also be a constant expression and the elements can be of any type
+//! that can be read.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::Reader;
+//!
+//! struct Limits;
+//!
+//! impl Limits
+//! {
+//!   const POINTS: usize = 2;
+//! }
+//!
+//! #[derive(structurs::Read, Debug, PartialEq)]
+//! struct Point
+//! {
+//!   x: i8,
+//!   y: i8,
+//! }
+//!
+//! #[derive(structurs::Read)]
+//! struct Shape
+//! {
+//!   points: [Point; Limits::POINTS],
+//! }
+//!
+//! let mut c = Cursor::new(vec![1, 2, 3, 4]);
+//! let shape = c.read_as::<Shape>().unwrap();
+//! assert_eq!([Point { x: 1, y: 2 }, Point { x: 3, y: 4 }], shape.points);
+//! ```
+//!
+//! Arrays can be nested, in which case the attributes apply to the elements of the innermost
+//! array.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Matrix
+//! {
+//!   #[be]
+//!   cells: [[u32; 2]; 3],
+//! }
+//!
+//! let bytes: Vec<u8> = (0..6u32).flat_map(|i| i.to_be_bytes()).collect();
+//! let mut c = Cursor::new(bytes.clone());
+//! let matrix = c.read_as::<Matrix>().unwrap();
+//! assert_eq!([[0, 1], [2, 3], [4, 5]], matrix.cells);
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! out.write_as(&matrix).unwrap();
+//! assert_eq!(bytes, out.into_inner());
+//! ```
+//!
+//! Padding fields are written as zeros. `#[pad]` writes the length of the field type worth of
+//! zero bytes, while `#[pad(bytes = N)]` writes N zero bytes.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::Writer;
+//!
+//! #[derive(structurs::Write)]
+//! struct Padded
+//! {
+//!   value: u8,
+//!   #[pad]
+//!   reserved: u32,
+//!   #[pad(bytes = 2)]
+//!   pad_field: structurs::Pad,
+//! }
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&Padded {
+//!   value: 7,
+//!   reserved: 0xFFFF_FFFF,
+//!   pad_field: structurs::Pad,
+//! })
+//! .unwrap();
+//! assert_eq!(vec![7, 0, 0, 0, 0, 0, 0], c.into_inner());
+//! ```
+//!
+//! Padding arrays take the size of all of their elements, whether their length is a literal, a
+//! constant or a const generic parameter.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! const RESERVED: usize = 3;
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Block<const N: usize>
+//! {
+//!   first: u8,
+//!   #[pad]
+//!   words: [u32; 8],
+//!   #[pad]
+//!   halves: [u16; RESERVED],
+//!   #[pad]
+//!   tail: [u8; N],
+//!   last: u8,
+//! }
+//!
+//! let block = Block::<2> {
+//!   first: 1,
+//!   words: [0xFFFF_FFFF; 8],
+//!   halves: [0xFFFF; RESERVED],
+//!   tail: [0xFF; 2],
+//!   last: 2,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&block).unwrap();
+//! let bytes = c.get_ref();
+//! assert_eq!(1 + 8 * 4 + 3 * 2 + 2 + 1, bytes.len());
+//! assert!(bytes[1..41].iter().all(|&byte| byte == 0));
+//! assert_eq!([1, 2], [bytes[0], bytes[41]]);
+//!
+//! c.set_position(0);
+//! let read = c.read_as::<Block<2>>().unwrap();
+//! assert_eq!(([0; 8], [0; RESERVED], [0; 2]), (read.words, read.halves, read.tail));
+//! assert_eq!(42, c.position());
+//! ```
+//!
+//! A struct-level endian attribute applies to every field that doesn't have its own.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[be]
+//! struct Header
+//! {
+//!   a: u16,
+//!   b: u16,
+//!   c: u16,
+//!   d: u16,
+//!   e: u16,
+//!   f: u16,
+//!   g: u16,
+//!   h: u16,
+//!   i: u16,
+//!   #[le]
+//!   j: u16,
+//! }
+//!
+//! let header = Header { a: 1, b: 2, c: 3, d: 4, e: 5, f: 6, g: 7, h: 8, i: 9, j: 10 };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&header).unwrap();
+//! let bytes = c.into_inner();
+//! assert_eq!(vec![0, 1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0, 7, 0, 8, 0, 9, 10, 0], bytes);
+//! assert_eq!(header, Cursor::new(bytes).read_as::<Header>().unwrap());
+//! ```
+//!
+//! A field can also be kept in the native endian of the CPU while the rest of the struct is not.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[be]
+//! struct Record
+//! {
+//!   length: u16,
+//!   #[ne]
+//!   cookie: u32,
+//!   #[native_endian]
+//!   ids: [u16; 2],
+//! }
+//!
+//! let record = Record { length: 1, cookie: 0x0102_0304, ids: [5, 6] };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! let bytes = c.into_inner();
+//! assert_eq!([0, 1], bytes[..2]);
+//! assert_eq!(0x0102_0304u32.to_ne_bytes(), bytes[2..6]);
+//! assert_eq!(5u16.to_ne_bytes(), bytes[6..8]);
+//! assert_eq!(6u16.to_ne_bytes(), bytes[8..]);
+//! assert_eq!(record, Cursor::new(bytes).read_as::<Record>().unwrap());
+//! ```
+//!
+//! Endian of a field is passed on to the fields of a nested struct.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Inner
+//! {
+//!   a: u32,
+//!   b: u32,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[be]
+//! struct Outer
+//! {
+//!   inner: Inner,
+//!   #[le]
+//!   inner_le: Inner,
+//! }
+//!
+//! let bytes = vec![0, 0, 0, 1, 0, 0, 0, 2, 3, 0, 0, 0, 4, 0, 0, 0];
+//! let outer = Cursor::new(bytes.clone()).read_as::<Outer>().unwrap();
+//! assert_eq!(Inner { a: 1, b: 2 }, outer.inner);
+//! assert_eq!(Inner { a: 3, b: 4 }, outer.inner_le);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&outer).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! Skipped fields don't take any bytes.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Entry
+//! {
+//!   value: u16,
+//!   #[skip]
+//!   cache: Option<String>,
+//! }
+//!
+//! let mut c = Cursor::new(vec![1, 0]);
+//! let entry = c.read_as::<Entry>().unwrap();
+//! assert_eq!(None, entry.cache);
+//!
+//! let entry = Entry { value: 1, cache: Some("cached".to_string()) };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&entry).unwrap();
+//! assert_eq!(vec![1, 0], c.into_inner());
+//! ```
+//!
+//! Padding and skipped fields of types that don't implement [`Default`] can be given a value.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::Reader;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Checksum(u32);
+//!
+//! impl Checksum
+//! {
+//!   fn unknown() -> Self
+//!   {
+//!     Checksum(u32::MAX)
+//!   }
+//! }
+//!
+//! #[derive(structurs::Read)]
+//! struct Entry
+//! {
+//!   value: u16,
+//!   #[skip]
+//!   #[default(Checksum::unknown())]
+//!   checksum: Checksum,
+//!   #[pad]
+//!   #[default(value * 2)]
+//!   doubled: u16,
+//! }
+//!
+//! let entry = Cursor::new(vec![1, 0, 0, 0]).read_as::<Entry>().unwrap();
+//! assert_eq!(Checksum::unknown(), entry.checksum);
+//! assert_eq!(2, entry.doubled);
+//! ```
+//!
+//! Reserved bytes must be zero, so files that use them for something else are rejected.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Entry
+//! {
+//!   kind: u8,
+//!   #[reserved(bytes = 3)]
+//!   _reserved: structurs::Pad,
+//!   #[le]
+//!   value: u32,
+//! }
+//!
+//! assert_eq!(8, Entry::SIZE);
+//! let entry = Entry {
+//!   kind: 1,
+//!   _reserved: structurs::Pad,
+//!   value: 2,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&entry).unwrap();
+//! assert_eq!(vec![1, 0, 0, 0, 2, 0, 0, 0], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(entry, c.read_as::<Entry>().unwrap());
+//!
+//! let err = Cursor::new(vec![1, 0, 9, 0, 2, 0, 0, 0]).read_as::<Entry>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! The default of a structure can be derived with the same values.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::Reader;
+//!
+//! #[derive(structurs::Read, structurs::StructursDefault, Debug, PartialEq)]
+//! struct Header
+//! {
+//!   version: u16,
+//!   #[length_prefix(u8)]
+//!   name: String,
+//!   table: [u32; 40],
+//!   #[pad(bytes = 4)]
+//!   _reserved: structurs::Pad,
+//!   #[skip]
+//!   #[default(version + 1)]
+//!   next_version: u16,
+//! }
+//!
+//! let header = Cursor::new(vec![0; 167]).read_as::<Header>().unwrap();
+//! assert_eq!(Header::default(), header);
+//! assert_eq!(1, header.next_version);
+//! ```
+//!
+//! Magic bytes are checked when they are read.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::Reader;
+//!
+//! #[derive(structurs::Read, Debug)]
+//! struct Riff
+//! {
+//!   #[magic = b"RIFF"]
+//!   signature: [u8; 4],
+//!   #[le]
+//!   size: u32,
+//! }
+//!
+//! let riff = Cursor::new(b"RIFF\x04\0\0\0".to_vec()).read_as::<Riff>().unwrap();
+//! assert_eq!(4, riff.size);
+//!
+//! let err = Cursor::new(b"RIFX\x04\0\0\0".to_vec()).read_as::<Riff>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! assert_eq!(
+//!   "failed to read field 'signature' at byte 0: invalid magic of field 'signature': \
+//!    expected b\"RIFF\", but found b\"RIFX\"",
+//!   err.to_string()
+//! );
+//! ```
+//!
+//! Fields can be checked against a value after they are read.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::Reader;
+//!
+//! const VERSION: u16 = 2;
+//!
+//! #[derive(structurs::Read, Debug)]
+//! struct Header
+//! {
+//!   #[assert_eq(VERSION)]
+//!   version: u16,
+//!   #[assert_eq = 0]
+//!   reserved: u8,
+//! }
+//!
+//! assert!(Cursor::new(vec![2, 0, 0]).read_as::<Header>().is_ok());
+//!
+//! let err = Cursor::new(vec![3, 0, 0]).read_as::<Header>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! assert_eq!(
+//!   "failed to read field 'version' at byte 0: field 'version' must be 2, but it is 3",
+//!   err.to_string()
+//! );
+//! ```
+//!
+//! A length prefixed `Vec` field is read as its length followed by its elements.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Point
+//! {
+//!   x: u16,
+//!   y: u16,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[be]
+//! struct Path
+//! {
+//!   #[length_prefix(u16)]
+//!   points: Vec<Point>,
+//!   #[length_prefix(u8)]
+//!   empty: Vec<u32>,
+//! }
+//!
+//! let bytes = vec![0, 2, 0, 1, 0, 2, 0, 3, 0, 4, 0];
+//! let path = Cursor::new(bytes.clone()).read_as::<Path>().unwrap();
+//! assert_eq!(vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }], path.points);
+//! assert!(path.empty.is_empty());
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&path).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! Tables that are only given by their length in bytes are read with `#[count_bytes]`.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Symbol
+//! {
+//!   #[le]
+//!   address: u32,
+//!   kind: u8,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Section
+//! {
+//!   #[le]
+//!   length: u16,
+//!   version: u8,
+//!   // The length of the section includes its 3 byte header.
+//!   #[count_bytes(length - 3)]
+//!   symbols: Vec<Symbol>,
+//! }
+//!
+//! let bytes = vec![13, 0, 1, 0x10, 0, 0, 0, 1, 0x20, 0, 0, 0, 2];
+//! let section = Cursor::new(bytes.clone()).read_as::<Section>().unwrap();
+//! let symbols = vec![Symbol { address: 0x10, kind: 1 }, Symbol { address: 0x20, kind: 2 }];
+//! assert_eq!(symbols, section.symbols);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&section).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//!
+//! let bytes = vec![12, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+//! let err = Cursor::new(bytes).read_as::<Section>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! The entries of a map are written in the order of its iterator, which for a `HashMap` isn't
+//! defined, so the bytes of a `HashMap` can differ between two writes of the same map.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Properties
+//! {
+//!   #[be]
+//!   #[length_prefix(u16)]
+//!   values: HashMap<u16, u32>,
+//! }
+//!
+//! let mut values = HashMap::new();
+//! values.insert(1, 10);
+//! values.insert(2, 20);
+//! values.insert(3, 30);
+//! let properties = Properties { values };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&properties).unwrap();
+//! assert_eq!(2 + 3 * 6, c.get_ref().len());
+//! assert_eq!([0, 3], c.get_ref()[..2]);
+//!
+//! c.set_position(0);
+//! assert_eq!(properties, c.read_as::<Properties>().unwrap());
+//! ```
+//!
+//! The count of a field can have another endian than its elements.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[be]
+//! struct Samples
+//! {
+//!   #[length_prefix(u32, le)]
+//!   values: Vec<u16>,
+//! }
+//!
+//! let bytes = vec![2, 0, 0, 0, 0x01, 0x02, 0x03, 0x04];
+//! let samples = Cursor::new(bytes.clone()).read_as::<Samples>().unwrap();
+//! assert_eq!(vec![0x0102, 0x0304], samples.values);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&samples).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! The elements can be arrays, in which case every element is an array of the same size and its
+//! values are read with the endian of the field.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Table
+//! {
+//!   #[be]
+//!   #[length_prefix(u16)]
+//!   rows: Vec<[u32; 4]>,
+//!   #[count(rows.len())]
+//!   keys: Vec<[u8; 2]>,
+//! }
+//!
+//! let table = Table {
+//!   rows: vec![[1, 2, 3, 4], [0x0506_0708, 0, 0, 0]],
+//!   keys: vec![[0xaa, 0xbb], [0xcc, 0xdd]],
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&table).unwrap();
+//! let bytes = c.get_ref();
+//! assert_eq!(2 + 2 * 16 + 2 * 2, bytes.len());
+//! assert_eq!([0, 2], bytes[..2]);
+//! assert_eq!([0, 0, 0, 1, 0, 0, 0, 2], bytes[2..10]);
+//! assert_eq!([5, 6, 7, 8], bytes[18..22]);
+//! assert_eq!([0xaa, 0xbb, 0xcc, 0xdd], bytes[34..]);
+//!
+//! c.set_position(0);
+//! assert_eq!(table, c.read_as::<Table>().unwrap());
+//! ```
+//!
+//! A length prefixed `String` field is read as its length in bytes followed by its UTF-8 bytes.
+//! Reading a string that is not valid UTF-8 fails with [`std::io::ErrorKind::InvalidData`].
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Entry
+//! {
+//!   #[le]
+//!   #[length_prefix(u32)]
+//!   name: String,
+//!   #[length_prefix(u8)]
+//!   comment: String,
+//! }
+//!
+//! let bytes = vec![4, 0, 0, 0, b'c', 0xC3, 0xA4, b't', 0];
+//! let entry = Cursor::new(bytes.clone()).read_as::<Entry>().unwrap();
+//! assert_eq!("cät", entry.name);
+//! assert_eq!("", entry.comment);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&entry).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//!
+//! let mut c = Cursor::new(vec![1, 0, 0, 0, 0xFF, 0]);
+//! assert_eq!(ErrorKind::InvalidData, c.read_as::<Entry>().unwrap_err().kind());
+//! ```
+//!
+//! A C string field is read up to and including its NUL terminator.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Names
+//! {
+//!   #[cstring]
+//!   first: String,
+//!   #[cstring]
+//!   second: Vec<u8>,
+//! }
+//!
+//! let bytes = b"hello\0\0".to_vec();
+//! let names = Cursor::new(bytes.clone()).read_as::<Names>().unwrap();
+//! assert_eq!("hello", names.first);
+//! assert!(names.second.is_empty());
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&names).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//!
+//! let names = Names { first: "a\0b".to_string(), second: Vec::new() };
+//! let err = Cursor::new(Vec::new()).write_as(&names).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! Other bytes can terminate a field as well.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Segment
+//! {
+//!   #[terminator = 0xff]
+//!   data: Vec<u8>,
+//!   marker: u8,
+//! }
+//!
+//! let bytes = vec![1, 0, 2, 0xff, 0xd9];
+//! let segment = Cursor::new(bytes.clone()).read_as::<Segment>().unwrap();
+//! assert_eq!(vec![1, 0, 2], segment.data);
+//! assert_eq!(0xd9, segment.marker);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&segment).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//!
+//! let err = Cursor::new(vec![1, 0, 2]).read_as::<Segment>().unwrap_err();
+//! assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+//! ```
+//!
+//! A fixed width string field always takes the same number of bytes.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Header
+//! {
+//!   #[fixed = 16]
+//!   name: String,
+//! }
+//!
+//! let header = Header { name: "hello".to_string() };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&header).unwrap();
+//! assert_eq!(b"hello\0\0\0\0\0\0\0\0\0\0\0", &c.get_ref()[..]);
+//! c.set_position(0);
+//! assert_eq!(header, c.read_as::<Header>().unwrap());
+//!
+//! let header = Header { name: "a name that is too long".to_string() };
+//! let err = Cursor::new(Vec::new()).write_as(&header).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! A fixed width `Vec<u8>` field is stored like a `[u8; N]` field, but keeps its bytes on the heap.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Key
+//! {
+//!   version: u8,
+//!   #[fixed = 64]
+//!   blob: Vec<u8>,
+//! }
+//!
+//! assert_eq!(65, Key::SIZE);
+//! let key = Key { version: 1, blob: (0..64).collect() };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&key).unwrap();
+//! assert_eq!(65, c.get_ref().len());
+//! assert_eq!(key.blob, c.get_ref()[1..]);
+//! c.set_position(0);
+//! assert_eq!(key, c.read_as::<Key>().unwrap());
+//!
+//! let err = Cursor::new(vec![1; 64]).read_as::<Key>().unwrap_err();
+//! assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+//!
+//! let key = Key { version: 1, blob: vec![0; 63] };
+//! let err = Cursor::new(Vec::new()).write_as(&key).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! Fixed-point numbers are read into floats.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! #[be]
+//! struct Transform
+//! {
+//!   #[fixed_point(frac = 16)]
+//!   scale: f64,
+//!   #[le]
+//!   #[fixed_point(u16, frac = 8)]
+//!   gain: f32,
+//! }
+//!
+//! assert_eq!(6, Transform::SIZE);
+//! let transform = Transform {
+//!   scale: 1.5,
+//!   gain: 0.25,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&transform).unwrap();
+//! assert_eq!(vec![0x00, 0x01, 0x80, 0x00, 0x40, 0x00], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(transform, c.read_as::<Transform>().unwrap());
+//!
+//! let transform = Transform {
+//!   scale: -1.5,
+//!   gain: -1.0,
+//! };
+//! let err = Cursor::new(Vec::new()).write_as(&transform).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! The number of elements of a `Vec` field can come from a field that was read before it.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Archive
+//! {
+//!   entry_count: u8,
+//!   reader: u8,
+//!   #[count(entry_count)]
+//!   entries: Vec<u16>,
+//!   #[count(reader as usize * 2)]
+//!   payload: Vec<u8>,
+//! }
+//!
+//! let bytes = vec![2, 1, 5, 0, 6, 0, 0xAB, 0xCD];
+//! let archive = Cursor::new(bytes.clone()).read_as::<Archive>().unwrap();
+//! assert_eq!(vec![5, 6], archive.entries);
+//! assert_eq!(vec![0xAB, 0xCD], archive.payload);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&archive).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! A length that is larger than the rest of the source fails when the source ends, without
+//! allocating memory for all of the elements it claims.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::Reader;
+//!
+//! #[derive(structurs::Read, Debug)]
+//! struct Table
+//! {
+//!   #[length_prefix(u64)]
+//!   rows: Vec<u64>,
+//! }
+//!
+//! let bytes = vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f, 1, 2, 3];
+//! let err = Cursor::new(bytes).read_as::<Table>().unwrap_err();
+//! assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+//! ```
+//!
+//! Lengths from untrusted data can be limited to what the format allows.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Message
+//! {
+//!   #[le]
+//!   #[length_prefix(u32)]
+//!   #[max = 1024]
+//!   body: Vec<u8>,
+//!   #[length_prefix(u8)]
+//!   #[max = 16]
+//!   name: String,
+//! }
+//!
+//! let bytes = vec![0xff, 0xff, 0xff, 0xff, 0];
+//! let err = Cursor::new(bytes).read_as::<Message>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//!
+//! // Any bytes either make a valid message or fail, without allocating more than the maximums.
+//! let mut seed = 1u32;
+//! for _ in 0..1000 {
+//!   let bytes: Vec<u8> = (0..32)
+//!     .map(|_| {
+//!       seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+//!       (seed >> 16) as u8
+//!     })
+//!     .collect();
+//!   if let Ok(message) = Cursor::new(bytes).read_as::<Message>() {
+//!     assert!(message.body.len() <= 1024 && message.name.len() <= 16);
+//!   }
+//! }
+//!
+//! let message = Message {
+//!   body: Vec::new(),
+//!   name: "a name that is too long".to_string(),
+//! };
+//! let err = Cursor::new(Vec::new()).write_as(&message).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! The number of elements can also be a constant.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! const CHANNELS: usize = 2;
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Frame
+//! {
+//!   #[le]
+//!   #[repeat = 8]
+//!   samples: Vec<u16>,
+//!   #[repeat(CHANNELS)]
+//!   gains: Vec<u8>,
+//! }
+//!
+//! assert_eq!(18, Frame::SIZE);
+//! let frame = Frame {
+//!   samples: (1..=8).collect(),
+//!   gains: vec![3, 4],
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&frame).unwrap();
+//! assert_eq!(Frame::SIZE, c.get_ref().len());
+//!
+//! c.set_position(0);
+//! assert_eq!(frame, c.read_as::<Frame>().unwrap());
+//!
+//! let frame = Frame {
+//!   samples: vec![1, 2, 3],
+//!   gains: vec![3, 4],
+//! };
+//! let err = Cursor::new(Vec::new()).write_as(&frame).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! Associated constants and const generic parameters can be used as well. Attribute values that
+//! aren't literals must be given in parentheses, e.g. `#[repeat(Self::LEN)]`.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Palette
+//! {
+//!   #[repeat(Self::LEN)]
+//!   colors: Vec<[u8; 3]>,
+//! }
+//!
+//! impl Palette
+//! {
+//!   const LEN: usize = 4;
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Table<const N: usize>
+//! {
+//!   #[be]
+//!   #[repeat(N)]
+//!   offsets: Vec<u32>,
+//!   #[count(N)]
+//!   flags: Vec<u8>,
+//! }
+//!
+//! assert_eq!(12, Palette::SIZE);
+//! let palette = Palette { colors: vec![[0; 3], [1; 3], [2; 3], [3; 3]] };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&palette).unwrap();
+//! c.set_position(0);
+//! assert_eq!(palette, c.read_as::<Palette>().unwrap());
+//!
+//! let table = Table::<2> { offsets: vec![1, 2], flags: vec![3, 4] };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&table).unwrap();
+//! assert_eq!(vec![0, 0, 0, 1, 0, 0, 0, 2, 3, 4], *c.get_ref());
+//! c.set_position(0);
+//! assert_eq!(table, c.read_as::<Table<2>>().unwrap());
+//! ```
+//!
+//! Fields that take a few bits can share an integer.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! #[be]
+//! struct Ipv4Start
+//! {
+//!   #[bits = 4]
+//!   version: u8,
+//!   #[bits = 4]
+//!   ihl: u8,
+//!   #[bits = 6]
+//!   dscp: u8,
+//!   #[bits = 2]
+//!   ecn: u8,
+//!   #[bits = 16]
+//!   total_length: u16,
+//!   ttl: u8,
+//! }
+//!
+//! assert_eq!(5, Ipv4Start::SIZE);
+//! let start = Ipv4Start {
+//!   version: 4,
+//!   ihl: 5,
+//!   dscp: 46,
+//!   ecn: 1,
+//!   total_length: 0x0054,
+//!   ttl: 64,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&start).unwrap();
+//! assert_eq!(vec![0x45, 0xb9, 0x00, 0x54, 64], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(start, c.read_as::<Ipv4Start>().unwrap());
+//!
+//! let start = Ipv4Start { version: 16, ..start };
+//! let err = Cursor::new(Vec::new()).write_as(&start).unwrap_err();
+//! assert_eq!(ErrorKind::InvalidInput, err.kind());
+//! ```
+//!
+//! The bits can be taken from the least significant bit instead, like some hardware registers do.
+//!
+//! ```
+//! use structurs::Writer;
+//!
+//! #[derive(structurs::Write)]
+//! struct MsbFirst
+//! {
+//!   #[bits = 3]
+//!   kind: u8,
+//!   #[bits = 5]
+//!   length: u8,
+//! }
+//!
+//! #[derive(structurs::Write)]
+//! #[bit_order(lsb)]
+//! struct LsbFirst
+//! {
+//!   #[bits = 3]
+//!   kind: u8,
+//!   #[bits = 5]
+//!   length: u8,
+//! }
+//!
+//! #[derive(structurs::Write)]
+//! struct Mixed
+//! {
+//!   #[bits = 3]
+//!   kind: u8,
+//!   #[bits = 5]
+//!   length: u8,
+//!   version: u8,
+//!   #[bits = 3]
+//!   #[bit_order(lsb)]
+//!   flags: u8,
+//!   #[bits = 5]
+//!   count: u8,
+//! }
+//!
+//! let mut bytes = Vec::new();
+//! bytes.write_as(&MsbFirst { kind: 1, length: 2 }).unwrap();
+//! bytes.write_as(&LsbFirst { kind: 1, length: 2 }).unwrap();
+//! bytes.write_as(&Mixed { kind: 1, length: 2, version: 7, flags: 1, count: 2 }).unwrap();
+//! assert_eq!(vec![0b001_00010, 0b00010_001, 0b001_00010, 7, 0b00010_001], bytes);
+//! ```
+//!
+//! Optional fields can depend on a flag that was read before them.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   has_checksum: bool,
+//!   length: u8,
+//!   #[present_if(has_checksum)]
+//!   checksum: Option<u8>,
+//! }
+//!
+//! let record = Cursor::new(vec![1, 4, 0xFF]).read_as::<Record>().unwrap();
+//! assert_eq!(Some(0xFF), record.checksum);
+//!
+//! let record = Cursor::new(vec![0, 4, 0xFF]).read_as::<Record>().unwrap();
+//! assert_eq!(None, record.checksum);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! assert_eq!(vec![0, 4], c.into_inner());
+//! ```
+//!
+//! Fields can also depend on any condition over the fields that were read before them.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Header
+//! {
+//!   version: u8,
+//!   #[structurs(le, if = version >= 2)]
+//!   flags: u16,
+//!   #[structurs(if = version >= 3)]
+//!   extra: Option<u8>,
+//! }
+//!
+//! let v1 = Cursor::new(vec![1]).read_as::<Header>().unwrap();
+//! assert_eq!(Header { version: 1, flags: 0, extra: None }, v1);
+//!
+//! let v2 = Cursor::new(vec![2, 0x34, 0x12]).read_as::<Header>().unwrap();
+//! assert_eq!(Header { version: 2, flags: 0x1234, extra: None }, v2);
+//!
+//! let v3 = Cursor::new(vec![3, 0x34, 0x12, 7]).read_as::<Header>().unwrap();
+//! assert_eq!(Header { version: 3, flags: 0x1234, extra: Some(7) }, v3);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&v2).unwrap();
+//! assert_eq!(vec![2, 0x34, 0x12], c.into_inner());
+//! ```
+//!
+//! Aligned fields skip the bytes up to their alignment.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Entry
+//! {
+//!   kind: u8,
+//!   #[le]
+//!   #[align = 4]
+//!   value: u32,
+//! }
+//!
+//! let bytes = vec![7, 0, 0, 0, 0x78, 0x56, 0x34, 0x12];
+//! let entry = Cursor::new(bytes.clone()).read_as::<Entry>().unwrap();
+//! assert_eq!(Entry { kind: 7, value: 0x12345678 }, entry);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&entry).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! The alignment is counted from the start of the stream, so a nested struct aligns its fields
+//! wherever it starts.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Inner
+//! {
+//!   a: u8,
+//!   #[le]
+//!   #[align = 4]
+//!   b: u32,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Outer
+//! {
+//!   x: u8,
+//!   inner: Inner,
+//! }
+//!
+//! let outer = Outer {
+//!   x: 1,
+//!   inner: Inner { a: 2, b: 3 },
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&outer).unwrap();
+//! assert_eq!(vec![1, 2, 0, 0, 3, 0, 0, 0], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(outer, c.read_as::<Outer>().unwrap());
+//! assert_eq!(8, c.position());
+//! ```
+//!
+//! Padding can also fill the bytes up to an alignment, wherever the previous fields end.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Pad, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   kind: u8,
+//!   #[pad(bytes = 2)]
+//!   reserved: Pad,
+//!   #[pad(align = 8)]
+//!   align: Pad,
+//!   value: u64,
+//! }
+//!
+//! let record = Record {
+//!   kind: 1,
+//!   reserved: Pad,
+//!   align: Pad,
+//!   value: 2,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! assert_eq!(16, c.get_ref().len());
+//! assert_eq!(2, c.get_ref()[8]);
+//!
+//! c.set_position(0);
+//! assert_eq!(record, c.read_as::<Record>().unwrap());
+//! assert_eq!(16, c.position());
+//! ```
+//!
+//! Padding that comes before or after a field doesn't need a field of its own.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Header
+//! {
+//!   version: u8,
+//!   #[le]
+//!   #[pad_before(bytes = 4)]
+//!   length: u32,
+//! }
+//!
+//! let bytes = vec![1, 0xff, 0xff, 0xff, 0xff, 0x10, 0, 0, 0];
+//! let header = Cursor::new(bytes).read_as::<Header>().unwrap();
+//! assert_eq!(Header { version: 1, length: 16 }, header);
+//! assert_eq!(9, Header::SIZE);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&header).unwrap();
+//! assert_eq!(vec![1, 0, 0, 0, 0, 0x10, 0, 0, 0], c.into_inner());
+//! ```
+//!
+//! The padding can be grouped with the other attributes of the field. It comes before or after the
+//! field whatever the order of the attributes is.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Entry
+//! {
+//!   #[structurs(pad_before(bytes = 4), be)]
+//!   id: u32,
+//!   #[structurs(be, pad_after(bytes = 1), pad_before(bytes = 2))]
+//!   flags: u16,
+//! }
+//!
+//! let bytes = vec![0xff, 0xff, 0xff, 0xff, 0, 0, 0, 7, 0xff, 0xff, 0x01, 0x02, 0xff];
+//! let mut c = Cursor::new(bytes);
+//! let entry = c.read_as::<Entry>().unwrap();
+//! assert_eq!(Entry { id: 7, flags: 0x0102 }, entry);
+//! assert_eq!(13, c.position());
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&entry).unwrap();
+//! assert_eq!(vec![0, 0, 0, 0, 0, 0, 0, 7, 0, 0, 0x01, 0x02, 0], c.into_inner());
+//! ```
+//!
+//! Padding can be filled with other bytes than zeros, and checked when it is read.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Pad, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Label
+//! {
+//!   name: [u8; 3],
+//!   #[pad(bytes = 5, fill = 0x20, strict)]
+//!   spaces: Pad,
+//!   #[pad_after(bytes = 2, fill = 0xFF)]
+//!   version: u8,
+//! }
+//!
+//! let label = Label {
+//!   name: *b"abc",
+//!   spaces: Pad,
+//!   version: 1,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&label).unwrap();
+//! assert_eq!(b"abc     \x01\xFF\xFF".to_vec(), *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(label, c.read_as::<Label>().unwrap());
+//!
+//! let err = Cursor::new(b"abc  \0  \x01\xFF\xFF".to_vec()).read_as::<Label>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! The size of a struct can be checked when it is compiled.
+//!
+//! ```
+//! use structurs::ByteSize;
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize)]
+//! #[size = 8]
+//! struct Entry
+//! {
+//!   id: u32,
+//!   #[pad(bytes = 2)]
+//!   reserved: structurs::Pad,
+//!   flags: u16,
+//! }
+//!
+//! assert_eq!(8, Entry::SIZE);
+//! ```
+//!
+//! Reading can check that a struct takes as many bytes as the format says.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader};
+//!
+//! #[derive(structurs::Read, structurs::ByteSize, Debug, PartialEq)]
+//! #[exact_size]
+//! struct Entry
+//! {
+//!   id: u32,
+//!   flags: u16,
+//! }
+//!
+//! let entry = Cursor::new(vec![1, 0, 0, 0, 2, 0]).read_as::<Entry>().unwrap();
+//! assert_eq!(Entry { id: 1, flags: 2 }, entry);
+//!
+//! // The format has 8 bytes, but the struct is missing the field for the last 2.
+//! #[derive(structurs::Read, Debug)]
+//! #[exact_size]
+//! struct Header
+//! {
+//!   magic: u32,
+//!   version: u16,
+//! }
+//!
+//! impl ByteSize for Header
+//! {
+//!   const SIZE: usize = 8;
+//! }
+//!
+//! let err = Cursor::new(vec![0; 8]).read_as::<Header>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! Fields with an encoding of their own can be read and written by functions.
+//!
+//! ```
+//! use std::io::{self, Cursor};
+//! use structurs::{Reader, Writer};
+//!
+//! fn read_varint<R: io::Read>(reader: &mut R) -> io::Result<u32>
+//! {
+//!   let mut value = 0;
+//!   for shift in (0..32).step_by(7) {
+//!     let byte = reader.read_as::<u8>()?;
+//!     value |= u32::from(byte & 0x7F) << shift;
+//!     if byte & 0x80 == 0 {
+//!       return Ok(value);
+//!     }
+//!   }
+//!   Err(io::Error::new(io::ErrorKind::InvalidData, "varint is too long"))
+//! }
+//!
+//! fn write_varint<W: io::Write>(value: &u32, writer: &mut W) -> io::Result<()>
+//! {
+//!   let mut value = *value;
+//!   while value >= 0x80 {
+//!     writer.write_as(&(value as u8 | 0x80))?;
+//!     value >>= 7;
+//!   }
+//!   writer.write_as(&(value as u8))
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Message
+//! {
+//!   #[read_with(read_varint)]
+//!   #[write_with(write_varint)]
+//!   length: u32,
+//!   kind: u8,
+//! }
+//!
+//! let bytes = vec![0xE5, 0x8E, 0x26, 3];
+//! let message = Cursor::new(bytes.clone()).read_as::<Message>().unwrap();
+//! assert_eq!(Message { length: 624485, kind: 3 }, message);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&message).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! Integer fields can be stored as varints.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   #[varint]
+//!   id: u64,
+//!   #[varint]
+//!   delta: i32,
+//! }
+//!
+//! let bytes = vec![0x80, 0x01, 0x05];
+//! let record = Cursor::new(bytes.clone()).read_as::<Record>().unwrap();
+//! assert_eq!(Record { id: 128, delta: -3 }, record);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! A checksum at the end of a record detects corrupted bytes.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   data: [u8; 9],
+//!   #[le]
+//!   #[crc32]
+//!   crc: u32,
+//! }
+//!
+//! let record = Record {
+//!   data: *b"123456789",
+//!   crc: 0,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! let mut bytes = c.into_inner();
+//! assert_eq!(&0xcbf43926u32.to_le_bytes(), &bytes[9..]);
+//!
+//! let record = Cursor::new(bytes.clone()).read_as::<Record>().unwrap();
+//! assert_eq!(0xcbf43926, record.crc);
+//!
+//! bytes[4] ^= 1;
+//! let err = Cursor::new(bytes).read_as::<Record>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! A hash at the end of a record is checked the same way, with any hasher that implements
+//! [`structurs::Finish`].
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Finish, Reader, Update, Writer};
+//!
+//! /// 64-bit FNV-1a hash.
+//! struct Fnv(u64);
+//!
+//! impl Default for Fnv
+//! {
+//!   fn default() -> Self
+//!   {
+//!     Self(0xcbf2_9ce4_8422_2325)
+//!   }
+//! }
+//!
+//! impl Update for Fnv
+//! {
+//!   fn update(&mut self, data: &[u8])
+//!   {
+//!     for &byte in data {
+//!       self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100_0000_01b3);
+//!     }
+//!   }
+//! }
+//!
+//! impl Finish for Fnv
+//! {
+//!   type Output = u64;
+//!
+//!   fn finish(&self) -> u64
+//!   {
+//!     self.0
+//!   }
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   #[le]
+//!   id: u32,
+//!   #[length_prefix(u8)]
+//!   name: String,
+//!   #[le]
+//!   #[digest(Fnv)]
+//!   hash: u64,
+//! }
+//!
+//! let record = Record {
+//!   id: 7,
+//!   name: "seven".to_string(),
+//!   hash: 0,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&record).unwrap();
+//! let mut bytes = c.into_inner();
+//!
+//! let mut expected = Fnv::default();
+//! expected.update(&bytes[..10]);
+//! assert_eq!(expected.finish().to_le_bytes(), bytes[10..]);
+//!
+//! let record = Cursor::new(bytes.clone()).read_as::<Record>().unwrap();
+//! assert_eq!(expected.finish(), record.hash);
+//!
+//! bytes[6] ^= 1;
+//! let err = Cursor::new(bytes).read_as::<Record>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! Strings can be stored as UTF-16, like the strings of Windows resources.
+//!
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Names
+//! {
+//!   #[le]
+//!   #[utf16]
+//!   #[length_prefix(u16)]
+//!   ascii: String,
+//!   #[be]
+//!   #[utf16]
+//!   #[length_prefix(u8)]
+//!   euro: String,
+//!   #[le]
+//!   #[utf16]
+//!   #[length_prefix(u8)]
+//!   emoji: String,
+//! }
+//!
+//! let bytes = vec![
+//!   2, 0, b'H', 0, b'i', 0, // "Hi"
+//!   1, 0x20, 0xac, // "€" is a single code unit
+//!   2, 0x3d, 0xd8, 0x00, 0xde, // "😀" is a surrogate pair
+//! ];
+//! let names = Cursor::new(bytes.clone()).read_as::<Names>().unwrap();
+//! assert_eq!("Hi", names.ascii);
+//! assert_eq!("€", names.euro);
+//! assert_eq!("😀", names.emoji);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&names).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//!
+//! // A surrogate that isn't part of a pair isn't valid UTF-16.
+//! let bytes = vec![0, 0, 0, 1, 0x00, 0xd8];
+//! let err = Cursor::new(bytes).read_as::<Names>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! A payload without a length can take the rest of the bytes.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Packet
+//! {
+//!   kind: u8,
+//!   #[be]
+//!   sequence: u16,
+//!   #[rest]
+//!   payload: Vec<u8>,
+//! }
+//!
+//! for length in [0, 1, 1000] {
+//!   let packet = Packet {
+//!     kind: 3,
+//!     sequence: 7,
+//!     payload: (0..length).map(|i| i as u8).collect(),
+//!   };
+//!   let mut c = Cursor::new(Vec::new());
+//!   c.write_as(&packet).unwrap();
+//!   assert_eq!(3 + length, c.get_ref().len());
+//!
+//!   c.set_position(0);
+//!   assert_eq!(packet, c.read_as::<Packet>().unwrap());
+//! }
+//! ```
+//!
+//! The endian can be decided by a field that is read before, like the byte order mark of a TIFF
+//! header.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Endian, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Body
+//! {
+//!   magic: u16,
+//!   first_ifd: u32,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Tiff
+//! {
+//!   byte_order: [u8; 2],
+//!   #[endian(if byte_order == *b"MM" { Endian::Big } else { Endian::Little })]
+//!   body: Body,
+//! }
+//!
+//! let little = Cursor::new(b"II\x2A\x00\x08\x00\x00\x00".to_vec()).read_as::<Tiff>().unwrap();
+//! let big = Cursor::new(b"MM\x00\x2A\x00\x00\x00\x08".to_vec()).read_as::<Tiff>().unwrap();
+//! assert_eq!(Body { magic: 42, first_ifd: 8 }, little.body);
+//! assert_eq!(little.body, big.body);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&big).unwrap();
+//! assert_eq!(b"MM\x00\x2A\x00\x00\x00\x08".to_vec(), c.into_inner());
+//! ```
+//!
+//! Grouped and separate attributes can be mixed.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[structurs(be)]
+//! struct Header
+//! {
+//!   #[structurs(le, length_prefix(u16))]
+//!   name: String,
+//!   #[structurs(pad(bytes = 2), default(7))]
+//!   reserved: u8,
+//!   #[le]
+//!   #[structurs(assert_eq = 1)]
+//!   version: u16,
+//!   size: u32,
+//! }
+//!
+//! let bytes = vec![2, 0, b'h', b'i', 0, 0, 1, 0, 0, 0, 0, 9];
+//! let header = Cursor::new(bytes.clone()).read_as::<Header>().unwrap();
+//! assert_eq!(Header { name: "hi".to_string(), reserved: 7, version: 1, size: 9 }, header);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&header).unwrap();
+//! assert_eq!(bytes, c.into_inner());
+//! ```
+//!
+//! Tuple structs are supported as well. The attributes can be used on their fields the same way.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Header(#[be] u32, [u8; 4]);
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Record
+//! {
+//!   header: Header,
+//!   #[le]
+//!   length: u16,
+//! }
+//!
+//! let mut c = Cursor::new(vec![0, 0, 0, 1, b'a', b'b', b'c', b'd', 4, 0]);
+//! let record = c.read_as::<Record>().unwrap();
+//! assert_eq!(Header(1, *b"abcd"), record.header);
+//! assert_eq!(4, record.length);
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! out.write_as(&record).unwrap();
+//! assert_eq!(c.into_inner(), out.into_inner());
+//! ```
+//!
+//! A newtype is read and written as the value it wraps, without attributes on its field. An endian
+//! on the newtype is always used for it, while a newtype without one uses the endian of the field
+//! that has it as its type.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! #[repr(transparent)]
+//! #[be]
+//! struct Id(u32);
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[repr(transparent)]
+//! struct Port(u16);
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[le]
+//! struct Endpoint
+//! {
+//!   id: Id,
+//!   port: Port,
+//! }
+//!
+//! assert_eq!(4, Id::SIZE);
+//! assert_eq!(Id(0x0102), Cursor::new(vec![0, 0, 1, 2]).read_as::<Id>().unwrap());
+//!
+//! let endpoint = Endpoint {
+//!   id: Id(0x0102),
+//!   port: Port(0x0304),
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&endpoint).unwrap();
+//! assert_eq!(vec![0, 0, 1, 2, 4, 3], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(endpoint, c.read_as::<Endpoint>().unwrap());
+//! ```
+//!
+//! Unit structs don't have any fields, so they are read from and written as zero bytes.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct Marker;
+//!
+//! let mut c = Cursor::new(vec![1, 2, 3]);
+//! assert_eq!(Marker, c.read_as::<Marker>().unwrap());
+//! assert_eq!(0, c.position());
+//! c.write_as(&Marker).unwrap();
+//! assert_eq!(0, c.position());
+//! ```
+//!
+//! Structs whose fields are all padding or skipped don't have any data either, they are read from
+//! and written as their padding only.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Reserved
+//! {
+//!   #[pad(bytes = 4)]
+//!   _reserved: structurs::Pad,
+//!   #[skip]
+//!   loaded: bool,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Ignored(#[skip] u32);
+//!
+//! assert_eq!(4, Reserved::SIZE);
+//! assert_eq!(0, Ignored::SIZE);
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&Reserved { _reserved: structurs::Pad, loaded: true }).unwrap();
+//! c.write_as(&Ignored(7)).unwrap();
+//! assert_eq!(vec![0, 0, 0, 0], *c.get_ref());
+//!
+//! c.set_position(0);
+//! let reserved = c.read_as::<Reserved>().unwrap();
+//! assert_eq!(Reserved { _reserved: structurs::Pad, loaded: false }, reserved);
+//! assert_eq!(Ignored(0), c.read_as::<Ignored>().unwrap());
+//! assert_eq!(4, c.position());
+//! ```
+//!
+//! Enums are read by reading the tag and then the fields of the variant it selects.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[repr(u8)]
+//! enum Command
+//! {
+//!   Stop,
+//!   Move(#[be] i16, #[be] i16) = 4,
+//!   Say([u8; 2]),
+//! }
+//!
+//! let commands = [Command::Stop, Command::Move(-1, 2), Command::Say(*b"hi")];
+//! let mut c = Cursor::new(Vec::new());
+//! for command in &commands {
+//!   c.write_as(command).unwrap();
+//! }
+//! assert_eq!(vec![0, 4, 0xFF, 0xFF, 0, 2, 5, b'h', b'i'], *c.get_ref());
+//!
+//! c.set_position(0);
+//! for command in commands {
+//!   assert_eq!(command, c.read_as::<Command>().unwrap());
+//! }
+//!
+//! let err = Cursor::new(vec![1]).read_as::<Command>().unwrap_err();
+//! assert_eq!("1 is not the tag of any variant of enum 'Command'", err.to_string());
+//! ```
+//!
+//! The tag is stored in the endian of the enum, like its fields.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[be]
+//! #[tag(u16)]
+//! enum Record
+//! {
+//!   Empty = 0x0102,
+//!   Value = 0x0304,
+//! }
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&Record::Empty).unwrap();
+//! c.write_as(&Record::Value).unwrap();
+//! assert_eq!(vec![1, 2, 3, 4], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(Record::Empty, c.read_as::<Record>().unwrap());
+//! assert_eq!(Record::Value, c.read_as::<Record>().unwrap());
+//! ```
+//!
+//! The fields of variants with named fields can have attributes like the fields of a struct.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! #[tag(u8)]
+//! enum Packet
+//! {
+//!   Ack,
+//!   Data
+//!   {
+//!     #[be]
+//!     seq: u32,
+//!     #[pad(bytes = 2)]
+//!     reserved: structurs::Pad,
+//!     payload: [u8; 8],
+//!   },
+//! }
+//!
+//! let packets = [
+//!   Packet::Data { seq: 0x0102, reserved: structurs::Pad, payload: *b"payload!" },
+//!   Packet::Ack,
+//! ];
+//! let mut c = Cursor::new(Vec::new());
+//! for packet in &packets {
+//!   c.write_as(packet).unwrap();
+//! }
+//! let bytes = c.get_ref();
+//! assert_eq!([1, 0, 0, 1, 2, 0, 0], bytes[..7]);
+//! assert_eq!(b"payload!", &bytes[7..15]);
+//! assert_eq!([0], bytes[15..]);
+//!
+//! c.set_position(0);
+//! for packet in packets {
+//!   assert_eq!(packet, c.read_as::<Packet>().unwrap());
+//! }
+//! ```
+//!
+//! Enums without fields are stored as just their tag, so they can be used as fields that can only
+//! have the values of their variants. They implement [`structurs::ByteSize`] as well.
 //!
-//! The following is the list of attributes that can be used to mark the fields of structures.
+//! ```
+//! use std::io::{Cursor, ErrorKind};
+//! use structurs::{ByteSize, Reader, Writer};
 //!
-//! - `#[le]`, This denotes that the field is in little-endian format.
-//! - `#[be]`, This denotes that the field is in big-endian format.
-//! - `#[ne]`, This denotes that the field is in CPU's native endian format. Most CPU's will use
-//!   little-endian format.
-//! - `#[pad]`, This denotes that the field is a padding and is not important. In this case the
-//!   field will be initialized to its default value using [`Default::default`]. By default the
-//!   length of the field type worth of bytes will be read from the reader. You can also pass a
-//!   `bytes` value to this attribute. `#[pad(bytes = N)]` means that N bytes should be read from
-//!   the reader in which case field type is not important and should be [`structurs::Pad`].
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! #[repr(u16)]
+//! enum Compression
+//! {
+//!   None = 1,
+//!   Lzw = 5,
+//!   Deflate = 8,
+//! }
 //!
-//! ## Example
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Image
+//! {
+//!   width: u8,
+//!   #[be]
+//!   compression: Compression,
+//! }
+//!
+//! assert_eq!(3, Image::SIZE);
+//!
+//! let image = Image {
+//!   width: 16,
+//!   compression: Compression::Lzw,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&image).unwrap();
+//! assert_eq!(vec![16, 0, 5], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(image, c.read_as::<Image>().unwrap());
+//!
+//! let err = Cursor::new(vec![16, 0, 2]).read_as::<Image>().unwrap_err();
+//! assert_eq!(ErrorKind::InvalidData, err.kind());
+//! ```
+//!
+//! Type parameters that are used by the fields are required to implement the trait that is derived,
+//! so generic structs don't need to spell out these bounds.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Tagged<T, const N: usize>
+//! {
+//!   tag: [u8; N],
+//!   #[be]
+//!   value: T,
+//! }
+//!
+//! let tagged = Tagged {
+//!   tag: *b"ID",
+//!   value: 0x1234u16,
+//! };
+//! assert_eq!(4, Tagged::<u16, 2>::SIZE);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&tagged).unwrap();
+//! assert_eq!(vec![b'I', b'D', 0x12, 0x34], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(tagged, c.read_as::<Tagged<u16, 2>>().unwrap());
+//! ```
+//!
+//! Const generic parameters can give the length of arrays of any element type, including padding
+//! and empty arrays.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use structurs::{ByteSize, Reader, Writer};
 //!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Point
+//! {
+//!   x: i16,
+//!   y: i16,
+//! }
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Buf<const N: usize>
+//! {
+//!   data: [u8; N],
+//!   points: [Point; N],
+//!   #[pad]
+//!   reserved: [u16; N],
+//! }
+//!
+//! let buf = Buf::<16> {
+//!   data: [7; 16],
+//!   points: core::array::from_fn(|i| Point { x: i as i16, y: -(i as i16) }),
+//!   reserved: [0; 16],
+//! };
+//! assert_eq!(112, Buf::<16>::SIZE);
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&buf).unwrap();
+//! assert_eq!(112, c.get_ref().len());
+//! c.set_position(0);
+//! assert_eq!(buf, c.read_as::<Buf<16>>().unwrap());
+//!
+//! let buf = Buf::<0> {
+//!   data: [],
+//!   points: [],
+//!   reserved: [],
+//! };
+//! assert_eq!(0, Buf::<0>::SIZE);
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&buf).unwrap();
+//! assert!(c.get_ref().is_empty());
+//! assert_eq!(buf, c.read_as::<Buf<0>>().unwrap());
+//! ```
+//!
+//! `PhantomData` fields are skipped, so marker types don't need to be readable or writable.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use std::marker::PhantomData;
+//! use structurs::{ByteSize, Reader, Writer};
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Meters;
+//!
+//! #[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+//! struct Tagged<T>
+//! {
+//!   val: u32,
+//!   _p: PhantomData<T>,
+//! }
+//!
+//! let tagged = Tagged::<Meters> {
+//!   val: 7,
+//!   _p: PhantomData,
+//! };
+//! assert_eq!(4, Tagged::<Meters>::SIZE);
+//!
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&tagged).unwrap();
+//! assert_eq!(vec![7, 0, 0, 0], *c.get_ref());
+//!
+//! c.set_position(0);
+//! assert_eq!(tagged, c.read_as::<Tagged<Meters>>().unwrap());
+//! ```
+//!
+//! Structs can have lifetimes as long as the fields that have bytes don't borrow.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use std::marker::PhantomData;
+//! use structurs::{Reader, Writer};
+//!
+//! #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+//! struct View<'a>
+//! {
+//!   offset: u32,
+//!   _data: PhantomData<&'a [u8]>,
+//! }
+//!
+//! let view = View {
+//!   offset: 8,
+//!   _data: PhantomData,
+//! };
+//! let mut c = Cursor::new(Vec::new());
+//! c.write_as(&view).unwrap();
+//!
+//! c.set_position(0);
+//! assert_eq!(view, c.read_as::<View>().unwrap());
 //! ```
+//!
+//! ## Errors
+//!
+//! Invalid attributes are reported as compile errors pointing at the offending tokens.
+//!
+//! ```compile_fail
 //! #[derive(structurs::Read)]
 //! struct Test
 //! {
-//!   // This field will be read using structurs::Read::read function.
-//!   field_1: i64,
-//!   // This field will be read using structurs::PrimitiveRead::read_le function.
-//!   #[le]
-//!   field_2: i16,
-//!   // This field will not be read. But 8 bytes will be read from the reader and discarded.
-//!   #[pad(bytes = 8)]
+//!   // error: expected `bytes`
+//!   #[pad(byte = 4)]
 //!   pad_field: structurs::Pad,
 //! }
 //! ```
 //!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: 'bit_order' attribute needs a 'bits' attribute.
+//!   #[bit_order(lsb)]
+//!   value: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: conflicting endian attributes `le` and `be`
+//!   #[le]
+//!   #[be]
+//!   value: u32,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: duplicate attribute `count`
+//!   count: u8,
+//!   #[structurs(count(count), count(count))]
+//!   entries: Vec<u8>,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Test
+//! {
+//!   // error: attribute `size` can only be used on a struct or an enum, not on a field
+//!   #[size = 7]
+//!   a: u8,
+//!   b: u32,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Test
+//! {
+//!   // error: attribute `tag` can only be used on a struct or an enum, not on a field
+//!   #[tag(u8)]
+//!   a: u8,
+//!   b: u32,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read, structurs::Write)]
+//! struct Test
+//! {
+//!   // error: attribute `exact_size` can only be used on a struct or an enum, not on a field
+//!   #[exact_size]
+//!   a: u8,
+//!   b: u32,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: 'count' attribute of field 'entries' refers to field 'entry_count' which isn't read
+//!   // before it.
+//!   #[count(entry_count)]
+//!   entries: Vec<u8>,
+//!   entry_count: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: 'length_prefix', 'count', 'count_bytes' and 'repeat' attributes can only be used on
+//!   // Vec<T>, String, Cow, HashMap<K, V> and BTreeMap<K, V> fields.
+//!   #[length_prefix(u8)]
+//!   entries: [u8; 4],
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test<'a>
+//! {
+//!   // error: fields that borrow can't be read or written, use an owned type like Vec<T> or
+//!   // Cow<'a, T> instead.
+//!   data: &'a [u8],
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: 'rest' attribute can only be used on the last field.
+//!   #[rest]
+//!   payload: Vec<u8>,
+//!   crc: u32,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Read)]
+//! struct Test
+//! {
+//!   // error: 'bits' fields have 12 bits, which is not the size of an integer. A field for the
+//!   // unused bits can fill them up to 8, 16, 32, 64 or 128 bits.
+//!   #[bits = 4]
+//!   kind: u8,
+//!   #[bits = 8]
+//!   flags: u8,
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(structurs::Write)]
+//! struct Test
+//! {
+//!   name_offset: u8,
+//!   // error: 'offset' attribute is only supported by the 'Read' derive macro.
+//!   #[offset(name_offset)]
+//!   name: [u8; 4],
+//! }
+//! ```
+//!
 //! ## Note
 //!
-//! This macro currently only supports structs with named fields.
+//! `#[derive(structurs::StructursDefault)]` only supports structs.
 
-use std::io;
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "tokio")]
+mod asynchronous;
+mod borrow;
+mod boxed;
+mod crc;
+mod error;
+mod hash;
+pub mod io;
+mod net;
+mod num;
+#[doc(hidden)]
+pub mod private;
 mod read;
+mod size;
+mod time;
+mod varint;
 mod write;
 
-pub use read::{PrimitiveRead, Read, Reader};
-pub use write::{PrimitiveWrite, Write, Writer};
+#[cfg(feature = "tokio")]
+pub use asynchronous::{AsyncRead, AsyncWrite};
+pub use error::Error;
+pub use hash::{Finish, HashingReader, HashingWriter, Update};
+pub use read::{
+  from_reader, read_all, read_at_offset, CountingReader, PeekReader, PrimitiveRead, Read, ReadAll, ReadSeek, Reader,
+};
+pub use size::ByteSize;
+pub use varint::Varint;
+pub use write::{to_writer, write_all, CountingWriter, PrimitiveWrite, Write, Writer};
 
 #[cfg(feature = "derive")]
 pub use structurs_derive::*;
 
+/// Byte order of a value.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Endian
+{
+  /// Least significant byte comes first.
+  Little,
+  /// Most significant byte comes first.
+  Big,
+  /// Byte order of the CPU.
+  Native,
+}
+
 /// Data structure for padding fields.
 #[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
 pub struct Pad;
 
+impl ByteSize for Pad
+{
+  const SIZE: usize = 0;
+}
+
+/// Number of bytes that the elements of primitive arrays are read and written in at once.
+const ARRAY_CHUNK: usize = 1024;
+
+/// Reads an array of a primitive type of `B` bytes in chunks, converting the bytes of each element
+/// with `from_bytes`.
+#[inline]
+fn read_primitive_array<R, T, F, const B: usize, const N: usize>(reader: &mut R, from_bytes: F) -> io::Result<[T; N]>
+where
+  R: io::Read + ?Sized,
+  T: Copy + Default,
+  F: Fn([u8; B]) -> T,
+{
+  let mut array = [T::default(); N];
+  let mut buf = [0u8; ARRAY_CHUNK];
+  for chunk in array.chunks_mut(ARRAY_CHUNK / B) {
+    let buf = &mut buf[..chunk.len() * B];
+    reader.read_exact(buf)?;
+    for (value, bytes) in chunk.iter_mut().zip(buf.chunks_exact(B)) {
+      let mut value_bytes = [0u8; B];
+      value_bytes.copy_from_slice(bytes);
+      *value = from_bytes(value_bytes);
+    }
+  }
+  Ok(array)
+}
+
+/// Writes a slice of a primitive type of `B` bytes in chunks, converting each element into bytes
+/// with `to_bytes`.
+#[inline]
+fn write_primitive_slice<W, T, F, const B: usize>(slice: &[T], writer: &mut W, to_bytes: F) -> io::Result<()>
+where
+  W: io::Write + ?Sized,
+  T: Copy,
+  F: Fn(T) -> [u8; B],
+{
+  let mut buf = [0u8; ARRAY_CHUNK];
+  for chunk in slice.chunks(ARRAY_CHUNK / B) {
+    let buf = &mut buf[..chunk.len() * B];
+    for (value, bytes) in chunk.iter().zip(buf.chunks_exact_mut(B)) {
+      bytes.copy_from_slice(&to_bytes(*value));
+    }
+    writer.write_all(buf)?;
+  }
+  Ok(())
+}
+
+/// Reads an array of a primitive type. The bytes of a `u8` array are read into it directly.
+macro_rules! read_primitive_array {
+  (u8, $reader:ident, $endian:ident) => {{
+    let _ = $endian;
+    let mut array = [0u8; N];
+    $reader.read_exact(&mut array)?;
+    Ok(array)
+  }};
+  ($ty:ident, $reader:ident, $endian:ident) => {
+    match $endian {
+      Some(Endian::Little) => read_primitive_array($reader, <$ty>::from_le_bytes),
+      Some(Endian::Big) => read_primitive_array($reader, <$ty>::from_be_bytes),
+      Some(Endian::Native) | None => read_primitive_array($reader, <$ty>::from_ne_bytes),
+    }
+  };
+}
+
+/// Writes a slice of a primitive type. The bytes of a `u8` slice are written directly.
+macro_rules! write_primitive_slice {
+  (u8, $slice:ident, $writer:ident, $endian:ident) => {{
+    let _ = $endian;
+    $writer.write_all($slice)
+  }};
+  ($ty:ident, $slice:ident, $writer:ident, $endian:ident) => {
+    match $endian {
+      Some(Endian::Little) => write_primitive_slice($slice, $writer, <$ty>::to_le_bytes),
+      Some(Endian::Big) => write_primitive_slice($slice, $writer, <$ty>::to_be_bytes),
+      Some(Endian::Native) | None => write_primitive_slice($slice, $writer, <$ty>::to_ne_bytes),
+    }
+  };
+}
+
 macro_rules! impl_primitive {
-  ($ty:ty, $bytes:expr) => {
+  ($ty:ident, $bytes:expr) => {
+    impl ByteSize for $ty
+    {
+      const SIZE: usize = $bytes;
+    }
+
     impl PrimitiveRead for $ty
     {
       #[inline]
       fn read_le<R>(reader: &mut R) -> io::Result<Self>
       where
-        R: io::Read,
+        R: io::Read + ?Sized,
       {
         let mut buf: [u8; $bytes] = [0; $bytes];
         reader.read_exact(&mut buf)?;
@@ -88,7 +2644,7 @@ macro_rules! impl_primitive {
       #[inline]
       fn read_be<R>(reader: &mut R) -> io::Result<Self>
       where
-        R: io::Read,
+        R: io::Read + ?Sized,
       {
         let mut buf: [u8; $bytes] = [0; $bytes];
         reader.read_exact(&mut buf)?;
@@ -101,7 +2657,7 @@ macro_rules! impl_primitive {
       #[inline]
       fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
       where
-        W: io::Write,
+        W: io::Write + ?Sized,
       {
         writer.write_all(&self.to_le_bytes())
       }
@@ -109,7 +2665,7 @@ macro_rules! impl_primitive {
       #[inline]
       fn write_be<W>(&self, writer: &mut W) -> io::Result<()>
       where
-        W: io::Write,
+        W: io::Write + ?Sized,
       {
         writer.write_all(&self.to_be_bytes())
       }
@@ -120,11 +2676,31 @@ macro_rules! impl_primitive {
       #[inline]
       fn read<R>(reader: &mut R) -> io::Result<Self>
       where
-        R: io::Read,
+        R: io::Read + ?Sized,
         Self: PrimitiveRead,
       {
         Self::read_ne(reader)
       }
+
+      #[inline]
+      fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+      where
+        R: io::Read + ?Sized,
+      {
+        match endian {
+          Endian::Little => Self::read_le(reader),
+          Endian::Big => Self::read_be(reader),
+          Endian::Native => Self::read_ne(reader),
+        }
+      }
+
+      #[inline]
+      fn read_array<R, const N: usize>(reader: &mut R, endian: Option<Endian>) -> io::Result<[Self; N]>
+      where
+        R: io::Read + ?Sized,
+      {
+        read_primitive_array!($ty, reader, endian)
+      }
     }
 
     impl Write for $ty
@@ -132,11 +2708,37 @@ macro_rules! impl_primitive {
       #[inline]
       fn write<W>(&self, writer: &mut W) -> io::Result<()>
       where
-        W: io::Write,
+        W: io::Write + ?Sized,
         Self: PrimitiveWrite,
       {
         self.write_ne(writer)
       }
+
+      #[inline]
+      fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+      where
+        W: io::Write + ?Sized,
+      {
+        match endian {
+          Endian::Little => self.write_le(writer),
+          Endian::Big => self.write_be(writer),
+          Endian::Native => self.write_ne(writer),
+        }
+      }
+
+      #[inline]
+      fn write_slice<W>(slice: &[Self], writer: &mut W, endian: Option<Endian>) -> io::Result<()>
+      where
+        W: io::Write + ?Sized,
+      {
+        write_primitive_slice!($ty, slice, writer, endian)
+      }
+
+      #[inline]
+      fn size_hint(&self) -> usize
+      {
+        $bytes
+      }
     }
   };
 }
@@ -153,3 +2755,419 @@ impl_primitive!(i64, 8);
 impl_primitive!(i128, 16);
 impl_primitive!(f32, 4);
 impl_primitive!(f64, 8);
+
+/// `bool` is stored as a single byte. `0` is read as `false` and any other value is read as `true`
+/// rather than failing, since formats that store flags as bytes rarely agree on the value of
+/// `true`. `true` is always written as `1`.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write)]
+/// struct Flags
+/// {
+///   enabled: bool,
+///   bits: [bool; 8],
+/// }
+///
+/// let mut c = Cursor::new(vec![0xFF, 0, 1, 2, 0, 0, 0, 0, 0x80]);
+/// let flags = c.read_as::<Flags>().unwrap();
+/// assert!(flags.enabled);
+/// assert_eq!([false, true, true, false, false, false, false, true], flags.bits);
+///
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&flags).unwrap();
+/// assert_eq!(vec![1, 0, 1, 1, 0, 0, 0, 0, 1], c.into_inner());
+/// ```
+impl Read for bool
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(u8::read(reader)? != 0)
+  }
+}
+
+impl Write for bool
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    (*self as u8).write(writer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for bool
+{
+  const SIZE: usize = 1;
+}
+
+/// `()` is read from and written as zero bytes, so generic data types and `Option` fields can be
+/// used with a type that has no data.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{ByteSize, Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Message<T>
+/// {
+///   kind: u8,
+///   #[present_if(kind)]
+///   body: Option<T>,
+/// }
+///
+/// assert_eq!(0, <()>::SIZE);
+/// let message = Message { kind: 1, body: Some(()) };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&message).unwrap();
+/// assert_eq!(vec![1], *c.get_ref());
+///
+/// c.set_position(0);
+/// assert_eq!(message, c.read_as::<Message<()>>().unwrap());
+/// assert_eq!(1, c.position());
+/// ```
+impl Read for ()
+{
+  #[inline]
+  fn read<R>(_reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(())
+  }
+}
+
+impl Write for ()
+{
+  #[inline]
+  fn write<W>(&self, _writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    Ok(())
+  }
+}
+
+impl ByteSize for ()
+{
+  const SIZE: usize = 0;
+}
+
+/// Converts a value that was read into a `char`.
+fn char_from_u32(value: u32) -> io::Result<char>
+{
+  char::from_u32(value).ok_or_else(|| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      alloc::format!("{:#x} is not a valid unicode scalar value", value),
+    )
+  })
+}
+
+/// `char` is stored as its unicode scalar value in a `u32`. Reading a value that is not a valid
+/// unicode scalar value fails with [`std::io::ErrorKind::InvalidData`].
+///
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use structurs::{Reader, Writer};
+///
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_be(&'é').unwrap();
+/// c.write_le(&char::MAX).unwrap();
+/// c.write_le(&0xD800u32).unwrap();
+///
+/// c.set_position(0);
+/// assert_eq!('é', c.read_be::<char>().unwrap());
+/// assert_eq!(char::MAX, c.read_le::<char>().unwrap());
+/// assert_eq!(ErrorKind::InvalidData, c.read_le::<char>().unwrap_err().kind());
+/// ```
+impl PrimitiveRead for char
+{
+  #[inline]
+  fn read_le<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    char_from_u32(u32::read_le(reader)?)
+  }
+
+  #[inline]
+  fn read_be<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    char_from_u32(u32::read_be(reader)?)
+  }
+}
+
+impl PrimitiveWrite for char
+{
+  #[inline]
+  fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    (*self as u32).write_le(writer)
+  }
+
+  #[inline]
+  fn write_be<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    (*self as u32).write_be(writer)
+  }
+}
+
+impl Read for char
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Self::read_ne(reader)
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    char_from_u32(u32::read_endian(reader, endian)?)
+  }
+}
+
+impl Write for char
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.write_ne(writer)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    (*self as u32).write_endian(writer, endian)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for char
+{
+  const SIZE: usize = 4;
+}
+
+/// Arrays are read and written element by element, so arrays of any data type that can be read or
+/// written can be read or written, including arrays of arrays. Arrays of integers and floating
+/// point numbers read and write the bytes of many elements at once instead.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{Endian, Read, Reader, Write, Writer};
+///
+/// let mut c = Cursor::new(vec![1, 0, 2, 0, 3, 0, 1, 2, 3, 4, 5, 6]);
+/// assert_eq!([1u16, 2, 3], c.read_as::<[u16; 3]>().unwrap());
+/// assert_eq!([[1u8, 2, 3], [4, 5, 6]], c.read_as::<[[u8; 3]; 2]>().unwrap());
+///
+/// let mut c = Cursor::new(Vec::new());
+/// [[1u16, 2], [3, 4]].write_endian(&mut c, Endian::Big).unwrap();
+/// assert_eq!(vec![0, 1, 0, 2, 0, 3, 0, 4], c.into_inner());
+///
+/// let mut bytes = [0u8; 4096];
+/// let mut words = [0u32; 1024];
+/// for i in 0..4096 {
+///   bytes[i] = i as u8;
+/// }
+/// for i in 0..1024 {
+///   words[i] = i as u32;
+/// }
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&bytes).unwrap();
+/// words.write_endian(&mut c, Endian::Big).unwrap();
+/// assert_eq!(8192, c.get_ref().len());
+/// assert_eq!([0, 0, 0, 0, 0, 0, 0, 1], c.get_ref()[4096..4104]);
+///
+/// c.set_position(0);
+/// assert_eq!(bytes, c.read_as::<[u8; 4096]>().unwrap());
+/// assert_eq!(words, <[u32; 1024]>::read_endian(&mut c, Endian::Big).unwrap());
+/// ```
+impl<T, const N: usize> Read for [T; N]
+where
+  T: Read,
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    T::read_array(reader, None)
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    T::read_array(reader, Some(endian))
+  }
+}
+
+impl<T, const N: usize> Write for [T; N]
+where
+  T: Write,
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    T::write_slice(self, writer, None)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    T::write_slice(self, writer, Some(endian))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    self.iter().map(T::size_hint).sum()
+  }
+}
+
+macro_rules! impl_tuple {
+  ($(#[$attr:meta])* $($name:ident $index:tt),+) => {
+    $(#[$attr])*
+    impl<$($name),+> Read for ($($name,)+)
+    where
+      $($name: Read,)+
+    {
+      #[inline]
+      fn read<R>(reader: &mut R) -> io::Result<Self>
+      where
+        R: io::Read + ?Sized,
+      {
+        Ok(($($name::read(reader)?,)+))
+      }
+
+      #[inline]
+      fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+      where
+        R: io::Read + ?Sized,
+      {
+        Ok(($($name::read_endian(reader, endian)?,)+))
+      }
+    }
+
+    impl<$($name),+> Write for ($($name,)+)
+    where
+      $($name: Write,)+
+    {
+      #[inline]
+      fn write<W>(&self, writer: &mut W) -> io::Result<()>
+      where
+        W: io::Write + ?Sized,
+      {
+        $(self.$index.write(writer)?;)+
+        Ok(())
+      }
+
+      #[inline]
+      fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+      where
+        W: io::Write + ?Sized,
+      {
+        $(self.$index.write_endian(writer, endian)?;)+
+        Ok(())
+      }
+
+      #[inline]
+      fn size_hint(&self) -> usize
+      {
+        0 $(+ self.$index.size_hint())+
+      }
+    }
+
+    impl<$($name),+> ByteSize for ($($name,)+)
+    where
+      $($name: ByteSize,)+
+    {
+      const SIZE: usize = 0 $(+ $name::SIZE)+;
+    }
+  };
+}
+
+impl_tuple!(
+  /// Tuples are read and written element by element in order, like the fields of a struct.
+  ///
+  /// ```
+  /// use std::io::Cursor;
+  /// use structurs::{Reader, Writer};
+  ///
+  /// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+  /// struct Entry
+  /// {
+  ///   #[be]
+  ///   range: (u16, u16),
+  ///   value: (u8, [u8; 3], u32),
+  /// }
+  ///
+  /// let value = (7u8, *b"abc", 0xDEADBEEFu32);
+  /// let mut c = Cursor::new(Vec::new());
+  /// c.write_as(&value).unwrap();
+  /// assert_eq!(vec![7, b'a', b'b', b'c', 0xEF, 0xBE, 0xAD, 0xDE], *c.get_ref());
+  ///
+  /// c.set_position(0);
+  /// assert_eq!(value, c.read_as::<(u8, [u8; 3], u32)>().unwrap());
+  ///
+  /// let entry = Entry {
+  ///   range: (1, 2),
+  ///   value,
+  /// };
+  /// let mut c = Cursor::new(Vec::new());
+  /// c.write_as(&entry).unwrap();
+  /// assert_eq!([0, 1, 0, 2, 7], c.get_ref()[..5]);
+  ///
+  /// c.set_position(0);
+  /// assert_eq!(entry, c.read_as::<Entry>().unwrap());
+  /// ```
+  A 0
+);
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
@@ -0,0 +1,248 @@
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+use crate::{io, ByteSize, Endian, Read, Write};
+
+/// IPv4 addresses are stored as their 4 octets in network order, regardless of the endian.
+///
+/// ```
+/// use std::io::Cursor;
+/// use std::net::Ipv4Addr;
+/// use structurs::{Reader, Writer};
+///
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&Ipv4Addr::new(192, 168, 0, 1)).unwrap();
+/// assert_eq!(vec![192, 168, 0, 1], *c.get_ref());
+///
+/// c.set_position(0);
+/// assert_eq!(Ipv4Addr::new(192, 168, 0, 1), c.read_as::<Ipv4Addr>().unwrap());
+/// ```
+impl Read for Ipv4Addr
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(Ipv4Addr::from(<[u8; 4]>::read(reader)?))
+  }
+}
+
+impl Write for Ipv4Addr
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.octets().write(writer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for Ipv4Addr
+{
+  const SIZE: usize = 4;
+}
+
+/// IPv6 addresses are stored as their 16 octets in network order, regardless of the endian.
+///
+/// ```
+/// use std::io::Cursor;
+/// use std::net::Ipv6Addr;
+/// use structurs::{Reader, Writer};
+///
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&Ipv6Addr::LOCALHOST).unwrap();
+/// assert_eq!(1, c.get_ref()[15]);
+///
+/// c.set_position(0);
+/// assert_eq!(Ipv6Addr::LOCALHOST, c.read_as::<Ipv6Addr>().unwrap());
+/// ```
+impl Read for Ipv6Addr
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(Ipv6Addr::from(<[u8; 16]>::read(reader)?))
+  }
+}
+
+impl Write for Ipv6Addr
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.octets().write(writer)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for Ipv6Addr
+{
+  const SIZE: usize = 16;
+}
+
+/// IPv4 socket addresses are stored as the address followed by the port. The port is a `u16` that
+/// is read and written with the endian of the field, so it is in network order with `#[be]`.
+///
+/// ```
+/// use std::io::Cursor;
+/// use std::net::{Ipv4Addr, SocketAddrV4};
+/// use structurs::{Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Route
+/// {
+///   #[be]
+///   source: SocketAddrV4,
+///   #[le]
+///   destination: SocketAddrV4,
+/// }
+///
+/// let route = Route {
+///   source: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 8080),
+///   destination: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 8080),
+/// };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&route).unwrap();
+/// assert_eq!(
+///   vec![10, 0, 0, 1, 0x1F, 0x90, 10, 0, 0, 2, 0x90, 0x1F],
+///   *c.get_ref()
+/// );
+///
+/// c.set_position(0);
+/// assert_eq!(route, c.read_as::<Route>().unwrap());
+/// ```
+impl Read for SocketAddrV4
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(SocketAddrV4::new(Ipv4Addr::read(reader)?, u16::read(reader)?))
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(SocketAddrV4::new(Ipv4Addr::read(reader)?, u16::read_endian(reader, endian)?))
+  }
+}
+
+impl Write for SocketAddrV4
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.ip().write(writer)?;
+    self.port().write(writer)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.ip().write(writer)?;
+    self.port().write_endian(writer, endian)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for SocketAddrV4
+{
+  const SIZE: usize = 6;
+}
+
+/// IPv6 socket addresses are stored as the address followed by the port, like IPv4 socket
+/// addresses. The flow information and the scope id are not stored, so they are `0` when the
+/// address is read.
+///
+/// ```
+/// use std::io::Cursor;
+/// use std::net::{Ipv6Addr, SocketAddrV6};
+/// use structurs::{Endian, Read, Write};
+///
+/// let addr = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0);
+/// let mut c = Cursor::new(Vec::new());
+/// addr.write_endian(&mut c, Endian::Big).unwrap();
+/// assert_eq!(18, c.get_ref().len());
+/// assert_eq!([0x01, 0xBB], c.get_ref()[16..]);
+///
+/// c.set_position(0);
+/// assert_eq!(addr, SocketAddrV6::read_endian(&mut c, Endian::Big).unwrap());
+/// ```
+impl Read for SocketAddrV6
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(SocketAddrV6::new(Ipv6Addr::read(reader)?, u16::read(reader)?, 0, 0))
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    Ok(SocketAddrV6::new(Ipv6Addr::read(reader)?, u16::read_endian(reader, endian)?, 0, 0))
+  }
+}
+
+impl Write for SocketAddrV6
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.ip().write(writer)?;
+    self.port().write(writer)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.ip().write(writer)?;
+    self.port().write_endian(writer, endian)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for SocketAddrV6
+{
+  const SIZE: usize = 18;
+}
@@ -0,0 +1,107 @@
+use core::num::{
+  NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
+  NonZeroU8,
+};
+
+use crate::{io, ByteSize, Endian, Read, Write};
+
+macro_rules! impl_non_zero {
+  ($(#[$attr:meta])* $ty:ident, $int:ident) => {
+    $(#[$attr])*
+    impl Read for $ty
+    {
+      #[inline]
+      fn read<R>(reader: &mut R) -> io::Result<Self>
+      where
+        R: io::Read + ?Sized,
+      {
+        non_zero($ty::new($int::read(reader)?), stringify!($ty))
+      }
+
+      #[inline]
+      fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+      where
+        R: io::Read + ?Sized,
+      {
+        non_zero($ty::new($int::read_endian(reader, endian)?), stringify!($ty))
+      }
+    }
+
+    impl Write for $ty
+    {
+      #[inline]
+      fn write<W>(&self, writer: &mut W) -> io::Result<()>
+      where
+        W: io::Write + ?Sized,
+      {
+        self.get().write(writer)
+      }
+
+      #[inline]
+      fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+      where
+        W: io::Write + ?Sized,
+      {
+        self.get().write_endian(writer, endian)
+      }
+
+      #[inline]
+      fn size_hint(&self) -> usize
+      {
+        Self::SIZE
+      }
+    }
+
+    impl ByteSize for $ty
+    {
+      const SIZE: usize = $int::SIZE;
+    }
+  };
+}
+
+/// Fails with `InvalidData` if the integer that was read is zero.
+fn non_zero<T>(value: Option<T>, name: &str) -> io::Result<T>
+{
+  value.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, alloc::format!("{} can't be zero", name)))
+}
+
+impl_non_zero!(
+  /// The `NonZero` integer types are stored as the integer they contain, with the endian of the
+  /// field. Reading zero fails with [`std::io::ErrorKind::InvalidData`].
+  ///
+  /// ```
+  /// use std::io::{Cursor, ErrorKind};
+  /// use std::num::NonZeroU32;
+  /// use structurs::{Reader, Writer};
+  ///
+  /// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+  /// struct Entry
+  /// {
+  ///   #[be]
+  ///   id: NonZeroU32,
+  /// }
+  ///
+  /// let entry = Entry {
+  ///   id: NonZeroU32::new(0x0102).unwrap(),
+  /// };
+  /// let mut c = Cursor::new(Vec::new());
+  /// c.write_as(&entry).unwrap();
+  /// assert_eq!(vec![0, 0, 1, 2], *c.get_ref());
+  ///
+  /// c.set_position(0);
+  /// assert_eq!(entry, c.read_as::<Entry>().unwrap());
+  ///
+  /// let err = Cursor::new(vec![0, 0, 0, 0]).read_as::<Entry>().unwrap_err();
+  /// assert_eq!(ErrorKind::InvalidData, err.kind());
+  /// ```
+  NonZeroU8, u8
+);
+impl_non_zero!(NonZeroU16, u16);
+impl_non_zero!(NonZeroU32, u32);
+impl_non_zero!(NonZeroU64, u64);
+impl_non_zero!(NonZeroU128, u128);
+impl_non_zero!(NonZeroI8, i8);
+impl_non_zero!(NonZeroI16, i16);
+impl_non_zero!(NonZeroI32, i32);
+impl_non_zero!(NonZeroI64, i64);
+impl_non_zero!(NonZeroI128, i128);
@@ -0,0 +1,583 @@
+//! Items used by the code generated by the derive macros. These are not part of the public API.
+
+use alloc::format;
+use alloc::string::String;
+use core::convert::{TryFrom, TryInto};
+
+use crate::io;
+use crate::{CountingReader, CountingWriter, Error};
+
+pub use crate::crc::{Checksum, Crc32Reader, Crc32Writer};
+pub use crate::hash::Hashing;
+pub use alloc::borrow::Cow;
+pub use alloc::vec;
+pub use alloc::vec::Vec;
+#[cfg(feature = "tokio")]
+pub use tokio;
+
+/// Builds an array by calling `f` with the index of each element in order. Stops at the first
+/// error.
+pub fn read_array<T, F, const N: usize>(mut f: F) -> io::Result<[T; N]>
+where
+  F: FnMut(usize) -> io::Result<T>,
+{
+  let mut elems = Vec::with_capacity(N);
+  for i in 0..N {
+    elems.push(f(i)?);
+  }
+  match <[T; N]>::try_from(elems) {
+    Ok(array) => Ok(array),
+    Err(_) => unreachable!("exactly N elements were read"),
+  }
+}
+
+/// Builds an array from the default of its element type, since `Default` is only implemented for
+/// arrays of up to 32 elements.
+pub fn default_array<T, const N: usize>() -> [T; N]
+where
+  T: Default,
+{
+  core::array::from_fn(|_| T::default())
+}
+
+/// Largest number of bytes that is allocated up front for the elements of a vector. The length of a
+/// vector usually comes from the source, so a corrupted or malicious length could otherwise
+/// allocate more memory than there is before the source runs out of bytes.
+const MAX_PREALLOCATION: usize = 64 * 1024;
+
+/// Number of elements of type `T` to allocate up front for a vector of `length` elements.
+pub fn capacity<T>(length: usize) -> usize
+{
+  length.min(MAX_PREALLOCATION / core::mem::size_of::<T>().max(1))
+}
+
+/// Builds a vector of `length` elements by calling `f` with the index of each element in order.
+/// Stops at the first error.
+pub fn read_vec<T, F>(length: usize, f: F) -> io::Result<Vec<T>>
+where
+  F: FnMut(usize) -> io::Result<T>,
+{
+  read_vec_into(Vec::new(), length, f)
+}
+
+/// Replaces the elements of `elems` with `length` elements built like [`read_vec`] does, reusing
+/// the memory of `elems`.
+pub fn read_vec_into<T, F>(mut elems: Vec<T>, length: usize, mut f: F) -> io::Result<Vec<T>>
+where
+  F: FnMut(usize) -> io::Result<T>,
+{
+  elems.clear();
+  elems.reserve(capacity::<T>(length));
+  for i in 0..length {
+    elems.push(f(i)?);
+  }
+  Ok(elems)
+}
+
+/// Builds a map of `length` entries by calling `f` for the key and the value of each entry in
+/// order. Stops at the first error.
+pub fn read_map<M, K, V, F>(length: usize, mut f: F) -> io::Result<M>
+where
+  M: Default + Extend<(K, V)>,
+  F: FnMut() -> io::Result<(K, V)>,
+{
+  let mut map = M::default();
+  for _ in 0..length {
+    map.extend(core::iter::once(f()?));
+  }
+  Ok(map)
+}
+
+/// Converts a length that was read from a source into `usize`.
+pub fn length_to_usize<L>(length: L) -> io::Result<usize>
+where
+  L: TryInto<usize>,
+{
+  length
+    .try_into()
+    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "length doesn't fit into usize"))
+}
+
+/// Rounds the value of a fixed-point field, already multiplied by 2 to the power of its fractional
+/// bits, to the integer it is written as. Fails if the integer doesn't fit between `min` and `max`.
+pub fn to_fixed_point(field: &str, value: f64, min: f64, max: f64) -> io::Result<f64>
+{
+  let int = if value < 0.0 { -(-value + 0.5) } else { value + 0.5 };
+  let int = int - int % 1.0;
+  if int >= min && int <= max {
+    Ok(int)
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("field '{}' doesn't fit in its fixed-point integer", field),
+    ))
+  }
+}
+
+/// Checks that a struct with `#[exact_size]` was read from as many bytes as its `ByteSize`.
+pub fn check_exact_size(name: &str, read: u64, size: usize) -> io::Result<()>
+{
+  if read == size as u64 {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("'{}' must be read from {} bytes, but {} bytes were read", name, size, read),
+    ))
+  }
+}
+
+/// Checks that a field has the number of elements that it is read with.
+pub fn check_length(field: &str, length: usize, expected: usize) -> io::Result<()>
+{
+  if length == expected {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("field '{}' must have {} elements, but it has {}", field, expected, length),
+    ))
+  }
+}
+
+/// Returns the number of elements of a field whose elements take `length` bytes. Fails if `length`
+/// isn't a multiple of the size of an element.
+pub fn count_from_bytes(field: &str, length: usize, size: usize) -> io::Result<usize>
+{
+  if size != 0 && length.is_multiple_of(size) {
+    Ok(length / size)
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("field '{}' takes {} bytes, which isn't a multiple of its element size {}", field, length, size),
+    ))
+  }
+}
+
+/// Checks that a field doesn't have more elements than its maximum. `kind` is the kind of the error
+/// if it does, which depends on whether the field is read or written.
+pub fn check_max(field: &str, length: usize, max: usize, kind: io::ErrorKind) -> io::Result<()>
+{
+  if length <= max {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      kind,
+      format!("field '{}' has {} elements, which is more than its maximum of {}", field, length, max),
+    ))
+  }
+}
+
+/// Checks that the value of a bit field fits into its bits.
+pub fn check_bits(field: &str, value: u128, width: u32) -> io::Result<u128>
+{
+  if width >= 128 || value >> width == 0 {
+    Ok(value)
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("value {} of field '{}' doesn't fit into {} bits", value, field, width),
+    ))
+  }
+}
+
+/// Converts a length into the type it is written as.
+pub fn length_from_usize<L>(length: usize) -> io::Result<L>
+where
+  L: TryFrom<usize>,
+{
+  L::try_from(length)
+    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "length doesn't fit into the length prefix"))
+}
+
+/// Reads a UTF-8 string of `length` bytes.
+pub fn read_string<R>(reader: &mut R, length: usize) -> io::Result<String>
+where
+  R: io::Read + ?Sized,
+{
+  read_string_into(reader, String::new(), length)
+}
+
+/// Replaces `string` with a UTF-8 string of `length` bytes, reusing the memory of `string`.
+pub fn read_string_into<R>(reader: &mut R, string: String, length: usize) -> io::Result<String>
+where
+  R: io::Read + ?Sized,
+{
+  // The bytes are read in chunks, so an invalid length fails at the end of the source instead of
+  // allocating all of it up front.
+  let mut bytes = string.into_bytes();
+  bytes.clear();
+  let mut chunk = [0u8; 256];
+  while bytes.len() < length {
+    let read = chunk.len().min(length - bytes.len());
+    reader.read_exact(&mut chunk[..read])?;
+    bytes.extend_from_slice(&chunk[..read]);
+  }
+  string_from_utf8(bytes)
+}
+
+/// Converts bytes that were read from a source into a string.
+pub fn string_from_utf8(bytes: Vec<u8>) -> io::Result<String>
+{
+  String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Converts UTF-16 code units that were read from a source into a string.
+pub fn string_from_utf16(units: Vec<u16>) -> io::Result<String>
+{
+  String::from_utf16(&units).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Reads all of the bytes until the end of the source.
+pub fn read_to_end<R>(reader: &mut R) -> io::Result<Vec<u8>>
+where
+  R: io::Read + ?Sized,
+{
+  let mut bytes = Vec::new();
+  let mut chunk = [0u8; 256];
+  loop {
+    match reader.read(&mut chunk) {
+      Ok(0) => return Ok(bytes),
+      Ok(read) => bytes.extend_from_slice(&chunk[..read]),
+      Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+      Err(err) => return Err(err),
+    }
+  }
+}
+
+/// Reads bytes until `terminator` is found. The terminator is consumed but is not included in the
+/// returned bytes.
+pub fn read_terminated<R>(reader: &mut R, terminator: u8) -> io::Result<Vec<u8>>
+where
+  R: io::Read + ?Sized,
+{
+  let mut bytes = Vec::new();
+  loop {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    if byte[0] == terminator {
+      return Ok(bytes);
+    }
+    bytes.push(byte[0]);
+  }
+}
+
+/// Writes the bytes followed by `terminator`. Fails if the bytes contain the terminator, since they
+/// couldn't be read back.
+pub fn write_terminated<W>(writer: &mut W, bytes: &[u8], terminator: u8) -> io::Result<()>
+where
+  W: io::Write + ?Sized,
+{
+  if bytes.contains(&terminator) {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("bytes contain the terminator {:#04x}", terminator),
+    ));
+  }
+  writer.write_all(bytes)?;
+  writer.write_all(&[terminator])
+}
+
+/// Reads a UTF-8 string that is stored in `width` bytes. The string is padded with trailing NUL
+/// bytes, which are not part of it.
+pub fn read_fixed_string<R>(reader: &mut R, width: usize) -> io::Result<String>
+where
+  R: io::Read + ?Sized,
+{
+  let mut bytes = vec![0u8; width];
+  reader.read_exact(&mut bytes)?;
+  while bytes.last() == Some(&0) {
+    bytes.pop();
+  }
+  string_from_utf8(bytes)
+}
+
+/// Reads the bytes of a `Vec<u8>` that is always stored in `width` bytes.
+pub fn read_fixed_bytes<R>(reader: &mut R, width: usize) -> io::Result<Vec<u8>>
+where
+  R: io::Read + ?Sized,
+{
+  let mut bytes = vec![0u8; width];
+  reader.read_exact(&mut bytes)?;
+  Ok(bytes)
+}
+
+/// Writes the bytes padded with NUL bytes to `width` bytes. Fails if there are more than `width`
+/// bytes.
+pub fn write_fixed<W>(writer: &mut W, bytes: &[u8], width: usize) -> io::Result<()>
+where
+  W: io::Write + ?Sized,
+{
+  if bytes.len() > width {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidInput,
+      format!("{} bytes don't fit into a field of {} bytes", bytes.len(), width),
+    ));
+  }
+  writer.write_all(bytes)?;
+  writer.write_all(&vec![0u8; width - bytes.len()])
+}
+
+/// Checks that the bytes that were read for a field are its magic bytes.
+pub fn check_magic(field: &str, found: &[u8], magic: &[u8]) -> io::Result<()>
+{
+  if found == magic {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!(
+        "invalid magic of field '{}': expected b\"{}\", but found b\"{}\"",
+        field,
+        magic.escape_ascii(),
+        found.escape_ascii()
+      ),
+    ))
+  }
+}
+
+/// Checks that the CRC32 that was read for a field is the one of the bytes that were read before
+/// it.
+pub fn check_crc32(field: &str, stored: u32, computed: u32) -> io::Result<()>
+{
+  if stored == computed {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!(
+        "CRC32 of field '{}' is {:#010x}, but the bytes before it have a CRC32 of {:#010x}",
+        field, stored, computed
+      ),
+    ))
+  }
+}
+
+/// Checks that the hash that was read for a `#[digest]` field is the one of the bytes that were
+/// read before it.
+pub fn check_digest<T>(field: &str, stored: &T, computed: &T) -> io::Result<()>
+where
+  T: PartialEq,
+{
+  if stored == computed {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("hash of field '{}' doesn't match the hash of the bytes before it", field),
+    ))
+  }
+}
+
+/// Checks that the value that was read for a field is the value it must have.
+pub fn check_eq<T>(field: &str, value: &T, expected: &T) -> io::Result<()>
+where
+  T: PartialEq + core::fmt::Debug,
+{
+  if value == expected {
+    Ok(())
+  } else {
+    Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("field '{}' must be {:?}, but it is {:?}", field, expected, value),
+    ))
+  }
+}
+
+/// Returns the error of reading a tag that doesn't select any of the variants of an enum.
+pub fn unknown_tag<T>(name: &str, tag: T) -> io::Error
+where
+  T: core::fmt::Display,
+{
+  io::Error::new(
+    io::ErrorKind::InvalidData,
+    format!("{} is not the tag of any variant of enum '{}'", tag, name),
+  )
+}
+
+/// Values that tell whether an optional field is present. Numbers are set when they are not zero.
+pub trait Flag
+{
+  fn is_set(&self) -> bool;
+}
+
+impl Flag for bool
+{
+  fn is_set(&self) -> bool
+  {
+    *self
+  }
+}
+
+macro_rules! impl_flag {
+  ($($ty:ty),*) => {
+    $(
+      impl Flag for $ty
+      {
+        fn is_set(&self) -> bool
+        {
+          *self != 0
+        }
+      }
+    )*
+  };
+}
+
+impl_flag!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Reads a value at `offset` from the start of the source and then seeks back to where the source
+/// was before, so the fields after it are read from the right position.
+pub fn read_at<R, O, T, F>(reader: &mut R, offset: O, f: F) -> io::Result<T>
+where
+  R: io::Seek + ?Sized,
+  O: TryInto<u64>,
+  F: FnOnce(&mut R) -> io::Result<T>,
+{
+  let offset = offset
+    .try_into()
+    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "offset doesn't fit into u64"))?;
+  let position = reader.stream_position()?;
+  reader.seek(io::SeekFrom::Start(offset))?;
+  let value = f(reader);
+  reader.seek(io::SeekFrom::Start(position))?;
+  value
+}
+
+/// Size of the chunks that padding is read and written in.
+const PADDING_CHUNK: usize = 256;
+
+/// Reads and discards `length` bytes of padding. If `fill` is given, the bytes must all be `fill`.
+pub fn read_padding<R>(reader: &mut R, length: usize, fill: Option<u8>) -> io::Result<()>
+where
+  R: io::Read + ?Sized,
+{
+  let mut chunk = [0u8; PADDING_CHUNK];
+  let mut left = length;
+  while left > 0 {
+    let read = left.min(PADDING_CHUNK);
+    reader.read_exact(&mut chunk[..read])?;
+    check_fill(&chunk[..read], fill)?;
+    left -= read;
+  }
+  Ok(())
+}
+
+/// Writes `length` bytes of padding that are all `fill`.
+pub fn write_padding<W>(writer: &mut W, length: usize, fill: u8) -> io::Result<()>
+where
+  W: io::Write + ?Sized,
+{
+  let chunk = [fill; PADDING_CHUNK];
+  let mut left = length;
+  while left > 0 {
+    let written = left.min(PADDING_CHUNK);
+    writer.write_all(&chunk[..written])?;
+    left -= written;
+  }
+  Ok(())
+}
+
+/// Checks that the bytes of a padding are all `fill`, if it is given.
+fn check_fill(bytes: &[u8], fill: Option<u8>) -> io::Result<()>
+{
+  match (fill, bytes.iter().find(|&&byte| Some(byte) != fill)) {
+    (Some(fill), Some(byte)) => Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("padding byte {:#04x} is not the fill byte {:#04x}", byte, fill),
+    )),
+    _ => Ok(()),
+  }
+}
+
+/// Reads and discards `length` bytes of padding asynchronously. If `fill` is given, the bytes must
+/// all be `fill`.
+#[cfg(feature = "tokio")]
+pub async fn read_padding_async<R>(reader: &mut R, length: usize, fill: Option<u8>) -> io::Result<()>
+where
+  R: tokio::io::AsyncRead + Unpin + Send,
+{
+  let mut chunk = [0u8; PADDING_CHUNK];
+  let mut left = length;
+  while left > 0 {
+    let read = left.min(PADDING_CHUNK);
+    tokio::io::AsyncReadExt::read_exact(reader, &mut chunk[..read]).await?;
+    check_fill(&chunk[..read], fill)?;
+    left -= read;
+  }
+  Ok(())
+}
+
+/// Reads `length` bytes asynchronously, in chunks so that an invalid length fails at the end of the
+/// source instead of allocating all of it up front.
+#[cfg(feature = "tokio")]
+pub async fn read_bytes_async<R>(reader: &mut R, length: usize) -> io::Result<Vec<u8>>
+where
+  R: tokio::io::AsyncRead + Unpin + Send,
+{
+  let mut bytes = Vec::with_capacity(capacity::<u8>(length));
+  let mut chunk = [0u8; 256];
+  while bytes.len() < length {
+    let read = chunk.len().min(length - bytes.len());
+    tokio::io::AsyncReadExt::read_exact(reader, &mut chunk[..read]).await?;
+    bytes.extend_from_slice(&chunk[..read]);
+  }
+  Ok(bytes)
+}
+
+/// Writes `length` bytes of padding that are all `fill` asynchronously.
+#[cfg(feature = "tokio")]
+pub async fn write_padding_async<W>(writer: &mut W, length: usize, fill: u8) -> io::Result<()>
+where
+  W: tokio::io::AsyncWrite + Unpin + Send,
+{
+  let chunk = [fill; PADDING_CHUNK];
+  let mut left = length;
+  while left > 0 {
+    let written = left.min(PADDING_CHUNK);
+    tokio::io::AsyncWriteExt::write_all(writer, &chunk[..written]).await?;
+    left -= written;
+  }
+  Ok(())
+}
+
+/// Reads and discards the bytes up to the next multiple of `alignment` from the start of the
+/// stream, which is `position` bytes before the start of the struct. If `fill` is given, the bytes
+/// must all be `fill`.
+pub fn align_read<R>(
+  reader: &mut CountingReader<R>,
+  position: u64,
+  alignment: usize,
+  fill: Option<u8>,
+) -> io::Result<()>
+where
+  R: io::Read,
+{
+  let padding = crate::write::padding(position + reader.count(), alignment)?;
+  read_padding(reader, padding, fill)
+}
+
+/// Writes `fill` bytes up to the next multiple of `alignment` from the start of the stream, which
+/// is `position` bytes before the start of the struct.
+pub fn align_write<W>(writer: &mut CountingWriter<W>, position: u64, alignment: usize, fill: u8) -> io::Result<()>
+where
+  W: io::Write,
+{
+  let padding = crate::write::padding(position + writer.count(), alignment)?;
+  write_padding(writer, padding, fill)
+}
+
+/// Reads a field of a struct with `f`. If it fails, the name of the field and where it starts are
+/// added to the error.
+pub fn read_field<R, T, F>(reader: &mut CountingReader<R>, field: &str, f: F) -> io::Result<T>
+where
+  F: FnOnce(&mut CountingReader<R>) -> io::Result<T>,
+{
+  let start = reader.count();
+  f(reader).map_err(|err| Error::in_field(field, Some((start, reader.count())), err))
+}
+
+/// Adds the name of a field to the error of reading it, when it is not known where it starts.
+pub fn field_error(field: &str, err: io::Error) -> io::Error
+{
+  Error::in_field(field, None, err)
+}
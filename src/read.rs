@@ -1,4 +1,7 @@
-use std::io;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{io, private, Endian, Varint};
 
 /// This trait can be used to read all kinds of data types that implement [`structurs::Read`] or
 /// ['structurs::PrimitiveRead'] from a source.
@@ -38,6 +41,40 @@ pub trait Reader: io::Read
     T::read(self)
   }
 
+  /// Reads a data type that needs to seek in the source, like a struct with `#[offset]` fields.
+  #[inline]
+  fn read_seek_as<T>(&mut self) -> io::Result<T>
+  where
+    T: ReadSeek,
+    Self: io::Seek + Sized,
+  {
+    T::read_seek(self)
+  }
+
+  /// Reads `length` elements into a vector. Only a bounded number of elements is allocated up front
+  /// and the vector grows as they are read, so a length that comes from untrusted data fails when
+  /// the source runs out instead of allocating all of it.
+  ///
+  /// ```
+  /// use std::io::{Cursor, ErrorKind};
+  /// use structurs::Reader;
+  ///
+  /// let mut c = Cursor::new(vec![1, 0, 2, 0]);
+  /// assert_eq!(vec![1u16, 2], c.read_vec::<u16>(2).unwrap());
+  ///
+  /// let mut c = Cursor::new(vec![1, 0, 2, 0]);
+  /// let err = c.read_vec::<u64>(usize::MAX / 8).unwrap_err();
+  /// assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+  /// ```
+  #[inline]
+  fn read_vec<T>(&mut self, length: usize) -> io::Result<Vec<T>>
+  where
+    T: Read,
+    Self: Sized,
+  {
+    private::read_vec(length, |_| T::read(self))
+  }
+
   #[inline]
   fn read_le<T>(&mut self) -> io::Result<T>
   where
@@ -55,12 +92,52 @@ pub trait Reader: io::Read
   {
     T::read_be(self)
   }
+
+  #[inline]
+  fn read_leb128<T>(&mut self) -> io::Result<T>
+  where
+    T: Varint,
+    Self: Sized,
+  {
+    T::read_leb128(self)
+  }
 }
 
-impl<T> Reader for T where T: io::Read {}
+impl<T> Reader for T where T: io::Read + ?Sized {}
 
 /// This trait can be used to read data types that can be represented in either big-endian or
-/// little-endian format like [`u64`].
+/// little-endian format like [`u64`]. It is implemented for all integer types, including the
+/// 128-bit ones, and floating point types.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Ids
+/// {
+///   #[le]
+///   first: u128,
+///   #[be]
+///   second: i128,
+///   #[be]
+///   all: [u128; 2],
+/// }
+///
+/// let ids = Ids {
+///   first: u128::MAX,
+///   second: -2,
+///   all: [u128::MAX, 1],
+/// };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&ids).unwrap();
+/// assert_eq!(64, c.get_ref().len());
+/// assert_eq!(0xFE, c.get_ref()[31]);
+/// assert_eq!(1, c.get_ref()[63]);
+///
+/// c.set_position(0);
+/// assert_eq!(ids, c.read_as::<Ids>().unwrap());
+/// ```
 pub trait PrimitiveRead
 {
   /// Reads a primitive type from a source in little-edian format.
@@ -77,7 +154,7 @@ pub trait PrimitiveRead
   /// ```
   fn read_le<R>(reader: &mut R) -> io::Result<Self>
   where
-    R: io::Read,
+    R: io::Read + ?Sized,
     Self: Sized;
 
   /// Reads a primitive type from a source in big-edian format.
@@ -94,7 +171,7 @@ pub trait PrimitiveRead
   /// ```
   fn read_be<R>(reader: &mut R) -> io::Result<Self>
   where
-    R: io::Read,
+    R: io::Read + ?Sized,
     Self: Sized;
 
   /// Reads a primitive type from a source in the native format that the CPU uses. Most of the CPU
@@ -103,7 +180,7 @@ pub trait PrimitiveRead
   #[inline]
   fn read_ne<R>(reader: &mut R) -> io::Result<Self>
   where
-    R: io::Read,
+    R: io::Read + ?Sized,
     Self: Sized,
   {
     Self::read_le(reader)
@@ -113,7 +190,7 @@ pub trait PrimitiveRead
   #[inline]
   fn read_ne<R>(reader: &mut R) -> io::Result<Self>
   where
-    R: io::Read,
+    R: io::Read + ?Sized,
     Self: Sized,
   {
     Self::read_be(reader)
@@ -122,10 +199,715 @@ pub trait PrimitiveRead
 
 /// This trait can be used to read data structures that are composed of other fields that implement
 /// [`structurs::Read`] or [`structurs::PrimitiveRead`]. See [`structurs_derive`].
+///
+/// The source doesn't need to be sized, so a data type can be read from a trait object directly.
+///
+/// ```
+/// use std::io::{Cursor, Read};
+///
+/// #[derive(structurs::Read, Debug, PartialEq)]
+/// struct Pair
+/// {
+///   #[le]
+///   a: u16,
+///   b: [u8; 2],
+/// }
+///
+/// let mut cursor = Cursor::new(vec![1, 0, 2, 3, 4, 0, 5, 6]);
+/// let reader: &mut dyn Read = &mut cursor;
+/// assert_eq!(Pair { a: 1, b: [2, 3] }, <Pair as structurs::Read>::read(reader).unwrap());
+/// let mut reader: &mut dyn Read = &mut cursor;
+/// assert_eq!(Pair { a: 4, b: [5, 6] }, <Pair as structurs::Read>::read(&mut reader).unwrap());
+/// ```
 pub trait Read
 {
   fn read<R>(reader: &mut R) -> io::Result<Self>
   where
-    R: io::Read,
+    R: io::Read + ?Sized,
     Self: Sized;
+
+  /// Reads the data type from a source using `endian` for the parts of it that don't have a fixed
+  /// endian. Data types whose representation doesn't depend on the endian are read using
+  /// [`structurs::Read::read`].
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+    Self: Sized,
+  {
+    let _ = endian;
+    Self::read(reader)
+  }
+
+  /// Reads the data type from a source behind a trait object. Derived implementations read the
+  /// fields whose types aren't primitive through it, so the code that reads a nested data type is
+  /// generated once instead of once for every source that the data types around it are read from.
+  ///
+  /// ```
+  /// use std::io::Cursor;
+  /// use structurs::Read;
+  ///
+  /// #[derive(structurs::Read, Debug, PartialEq)]
+  /// struct Point
+  /// {
+  ///   x: i16,
+  ///   y: i16,
+  /// }
+  ///
+  /// #[derive(structurs::Read, Debug, PartialEq)]
+  /// #[be]
+  /// struct Line
+  /// {
+  ///   from: Point,
+  ///   to: Point,
+  /// }
+  ///
+  /// let bytes = vec![0, 1, 0, 2, 0xFF, 0xFF, 0, 4];
+  /// let reader: &mut dyn std::io::Read = &mut Cursor::new(bytes.clone());
+  /// let line = Line::read_dyn_endian(reader, structurs::Endian::Big).unwrap();
+  /// assert_eq!(Line::read_endian(&mut Cursor::new(bytes), structurs::Endian::Big).unwrap(), line);
+  /// assert_eq!(Point { x: -1, y: 4 }, line.to);
+  /// ```
+  #[inline]
+  fn read_dyn(reader: &mut dyn io::Read) -> io::Result<Self>
+  where
+    Self: Sized,
+  {
+    Self::read(reader)
+  }
+
+  /// Reads the data type from a source behind a trait object using `endian` for the parts of it
+  /// that don't have a fixed endian. See [`structurs::Read::read_dyn`].
+  #[inline]
+  fn read_dyn_endian(reader: &mut dyn io::Read, endian: Endian) -> io::Result<Self>
+  where
+    Self: Sized,
+  {
+    Self::read_endian(reader, endian)
+  }
+
+  /// Reads the data type like [`structurs::Read::read_endian`] when `position` bytes of the stream
+  /// come before it. Derived implementations align their fields from the start of the stream, so a
+  /// nested struct is given the position of its field.
+  #[doc(hidden)]
+  #[inline]
+  fn read_endian_at<R>(reader: &mut R, endian: Endian, position: u64) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+    Self: Sized,
+  {
+    let _ = position;
+    Self::read_endian(reader, endian)
+  }
+
+  /// Reads the data type from a source behind a trait object like
+  /// [`structurs::Read::read_endian_at`].
+  #[doc(hidden)]
+  #[inline]
+  fn read_dyn_at(reader: &mut dyn io::Read, endian: Endian, position: u64) -> io::Result<Self>
+  where
+    Self: Sized,
+  {
+    Self::read_endian_at(reader, endian, position)
+  }
+
+  /// Reads an array of the data type, using `endian` if it is given like
+  /// [`structurs::Read::read_endian`]. Primitive types override it to read the bytes of many
+  /// elements at once.
+  #[doc(hidden)]
+  #[inline]
+  fn read_array<R, const N: usize>(reader: &mut R, endian: Option<Endian>) -> io::Result<[Self; N]>
+  where
+    R: io::Read + ?Sized,
+    Self: Sized,
+  {
+    match endian {
+      Some(endian) => private::read_array(|_| Self::read_endian(reader, endian)),
+      None => private::read_array(|_| Self::read(reader)),
+    }
+  }
+
+  /// Reads the data type from a source into an existing value. Derived implementations reuse the
+  /// memory of the `Vec` and `String` fields that have a `#[length_prefix]` or a `#[count]`, so
+  /// reading into the same value in a loop doesn't allocate them every time. The value is
+  /// unspecified if reading fails.
+  ///
+  /// ```
+  /// use std::io::Cursor;
+  /// use structurs::Read;
+  ///
+  /// #[derive(structurs::Read, Debug, Default)]
+  /// struct Packet
+  /// {
+  ///   id: u8,
+  ///   #[length_prefix(u8)]
+  ///   payload: Vec<u8>,
+  /// }
+  ///
+  /// let mut c = Cursor::new(vec![1, 3, 0xA, 0xB, 0xC, 2, 2, 0xD, 0xE]);
+  /// let mut packet = Packet::default();
+  /// packet.read_into(&mut c).unwrap();
+  /// assert_eq!(vec![0xA, 0xB, 0xC], packet.payload);
+  /// let buffer = packet.payload.as_ptr();
+  ///
+  /// packet.read_into(&mut c).unwrap();
+  /// assert_eq!(2, packet.id);
+  /// assert_eq!(vec![0xD, 0xE], packet.payload);
+  /// assert_eq!(buffer, packet.payload.as_ptr());
+  /// ```
+  #[inline]
+  fn read_into<R>(&mut self, reader: &mut R) -> io::Result<()>
+  where
+    R: io::Read + ?Sized,
+    Self: Sized,
+  {
+    self.read_into_endian(reader, Endian::Native)
+  }
+
+  /// Reads the data type from a source into an existing value using `endian` for the parts of it
+  /// that don't have a fixed endian. See [`structurs::Read::read_into`].
+  #[inline]
+  fn read_into_endian<R>(&mut self, reader: &mut R, endian: Endian) -> io::Result<()>
+  where
+    R: io::Read + ?Sized,
+    Self: Sized,
+  {
+    *self = Self::read_endian(reader, endian)?;
+    Ok(())
+  }
+
+  /// Reads the data type from the start of `buf` and returns it with the number of bytes that were
+  /// read. The bytes are read from `buf` directly, so no source has to be created for them.
+  ///
+  /// ```
+  /// use structurs::Read;
+  ///
+  /// #[derive(structurs::Read, Debug, PartialEq)]
+  /// struct Header
+  /// {
+  ///   kind: u8,
+  ///   #[be]
+  ///   length: u32,
+  ///   flags: [u8; 2],
+  /// }
+  ///
+  /// let buf = [1, 0, 0, 0, 9, 0xA, 0xB, 0xFF, 0xFF];
+  /// let (header, read) = Header::read_from_slice(&buf).unwrap();
+  /// assert_eq!(
+  ///   Header {
+  ///     kind: 1,
+  ///     length: 9,
+  ///     flags: [0xA, 0xB]
+  ///   },
+  ///   header
+  /// );
+  /// assert_eq!(7, read);
+  /// assert_eq!([0xFF, 0xFF], buf[read..]);
+  /// ```
+  #[inline]
+  fn read_from_slice(buf: &[u8]) -> io::Result<(Self, usize)>
+  where
+    Self: Sized,
+  {
+    let mut rest = buf;
+    let value = Self::read(&mut rest)?;
+    Ok((value, buf.len() - rest.len()))
+  }
+}
+
+/// This trait can be used to read data types that need to seek in the source while they are read,
+/// like structs that have `#[offset]` fields. It is implemented for every data type that implements
+/// [`structurs::Read`], so they can be used as fields of those structs.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::Reader;
+///
+/// #[derive(structurs::Read)]
+/// struct Header
+/// {
+///   name_offset: u8,
+///   #[offset(name_offset)]
+///   name: [u8; 2],
+///   version: u8,
+/// }
+///
+/// let mut c = Cursor::new(vec![4, 1, 0xFF, 0xFF, b'h', b'i']);
+/// let header = c.read_seek_as::<Header>().unwrap();
+/// assert_eq!(*b"hi", header.name);
+/// assert_eq!(1, header.version);
+/// assert_eq!(2, c.position());
+/// ```
+///
+/// Offsets are from the start of the source, so tables can be read in any order and structs that
+/// are read at an offset can have fields at offsets of their own. The position is restored after
+/// each of them.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::Reader;
+///
+/// #[derive(structurs::Read, Debug, PartialEq)]
+/// struct Section
+/// {
+///   name_offset: u8,
+///   #[offset(name_offset)]
+///   name: [u8; 4],
+///   size: u8,
+/// }
+///
+/// #[derive(structurs::Read, Debug, PartialEq)]
+/// struct Elf
+/// {
+///   symbols_offset: u8,
+///   sections_offset: u8,
+///   #[offset(sections_offset)]
+///   sections: [Section; 2],
+///   #[offset(symbols_offset)]
+///   symbols: [u8; 3],
+///   flags: u8,
+/// }
+///
+/// let bytes = vec![
+///   14, 5, 0x80, // header
+///   0, 0, // unused
+///   17, 10, 10, 20, // sections
+///   0, // unused
+///   b'd', b'a', b't', b'a', // name of the second section
+///   b'S', b'Y', b'M', // symbols
+///   b't', b'e', b'x', b't', // name of the first section
+/// ];
+/// let mut c = Cursor::new(bytes);
+/// let elf = c.read_seek_as::<Elf>().unwrap();
+/// assert_eq!(
+///   [
+///     Section {
+///       name_offset: 17,
+///       name: *b"text",
+///       size: 10
+///     },
+///     Section {
+///       name_offset: 10,
+///       name: *b"data",
+///       size: 20
+///     }
+///   ],
+///   elf.sections
+/// );
+/// assert_eq!(*b"SYM", elf.symbols);
+/// assert_eq!(0x80, elf.flags);
+/// assert_eq!(3, c.position());
+/// ```
+pub trait ReadSeek
+{
+  fn read_seek<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + io::Seek + ?Sized,
+    Self: Sized;
+
+  /// Reads the data type from a source using `endian` for the parts of it that don't have a fixed
+  /// endian. See [`structurs::Read::read_endian`].
+  #[inline]
+  fn read_seek_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + io::Seek + ?Sized,
+    Self: Sized,
+  {
+    let _ = endian;
+    Self::read_seek(reader)
+  }
+}
+
+impl<T> ReadSeek for T
+where
+  T: Read,
+{
+  #[inline]
+  fn read_seek<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + io::Seek + ?Sized,
+  {
+    T::read(reader)
+  }
+
+  #[inline]
+  fn read_seek_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + io::Seek + ?Sized,
+  {
+    T::read_endian(reader, endian)
+  }
+}
+
+/// Reads a `T` from `reader`, the same as `T::read(reader)`. The type can be given with a turbofish
+/// where it can't be inferred.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{from_reader, to_writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Version
+/// {
+///   major: u8,
+///   #[be]
+///   minor: u16,
+/// }
+///
+/// let mut bytes = Vec::new();
+/// to_writer(&Version { major: 1, minor: 2 }, &mut bytes).unwrap();
+/// assert_eq!(vec![1, 0, 2], bytes);
+///
+/// let version = from_reader::<Version, _>(&mut Cursor::new(&bytes)).unwrap();
+/// assert_eq!(Version { major: 1, minor: 2 }, version);
+/// assert_eq!(1, from_reader::<u8, _>(&mut &bytes[..]).unwrap());
+/// ```
+#[inline]
+pub fn from_reader<T, R>(reader: &mut R) -> io::Result<T>
+where
+  T: Read,
+  R: io::Read + ?Sized,
+{
+  T::read(reader)
+}
+
+/// Reads a `T` at `offset` from the start of `reader` and then seeks back to where `reader` was,
+/// like a field with `#[offset(expr)]` is read. To go on reading after the value instead, seek to
+/// the offset and use [`structurs::from_reader`].
+///
+/// ```
+/// use std::io::{Cursor, Seek, SeekFrom};
+/// use structurs::read_at_offset;
+///
+/// #[derive(structurs::Read, Debug, PartialEq)]
+/// struct Record
+/// {
+///   #[le]
+///   id: u32,
+///   flags: u8,
+/// }
+///
+/// let mut bytes = vec![0; 256];
+/// bytes[128..133].copy_from_slice(&[7, 0, 0, 0, 1]);
+/// let mut c = Cursor::new(bytes);
+/// c.seek(SeekFrom::Start(10)).unwrap();
+///
+/// let record = read_at_offset::<Record, _>(&mut c, 128).unwrap();
+/// assert_eq!(Record { id: 7, flags: 1 }, record);
+/// assert_eq!(10, c.position());
+///
+/// assert!(read_at_offset::<Record, _>(&mut c, 254).is_err());
+/// assert_eq!(10, c.position());
+/// ```
+#[inline]
+pub fn read_at_offset<T, R>(reader: &mut R, offset: u64) -> io::Result<T>
+where
+  T: Read,
+  R: io::Read + io::Seek + ?Sized,
+{
+  private::read_at(reader, offset, |reader| T::read(reader))
+}
+
+/// Returns an iterator over the records of type `T` that are read one after another from `reader`
+/// until it ends. The iterator ends when the source ends between two records, while a source that
+/// ends in the middle of a record yields an `UnexpectedEof` error. The iterator also ends after the
+/// first error.
+///
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use structurs::{read_all, write_all};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Entry
+/// {
+///   #[le]
+///   time: u32,
+///   #[length_prefix(u8)]
+///   message: String,
+/// }
+///
+/// let entries: Vec<Entry> = (0..100)
+///   .map(|i| Entry {
+///     time: i * 10,
+///     message: format!("entry {}", i),
+///   })
+///   .collect();
+/// let mut log = Vec::new();
+/// write_all(&mut log, &entries).unwrap();
+///
+/// let read: Vec<Entry> = read_all(Cursor::new(&log)).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(entries, read);
+///
+/// let mut records = read_all::<Entry, _>(Cursor::new(&log[..log.len() - 1]));
+/// assert_eq!(99, records.by_ref().take_while(Result::is_ok).count());
+/// assert!(records.next().is_none());
+///
+/// let err = read_all::<Entry, _>(Cursor::new(&log[..15])).nth(1).unwrap().unwrap_err();
+/// assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+/// ```
+pub fn read_all<T, R>(reader: R) -> ReadAll<T, R>
+where
+  T: Read,
+  R: io::Read,
+{
+  ReadAll {
+    reader: CountingReader::new(reader),
+    done: false,
+    record: PhantomData,
+  }
+}
+
+/// Iterator over the records of a source. See [`structurs::read_all`].
+pub struct ReadAll<T, R>
+{
+  reader: CountingReader<R>,
+  done: bool,
+  record: PhantomData<fn() -> T>,
+}
+
+impl<T, R> ReadAll<T, R>
+{
+  pub fn into_inner(self) -> R
+  {
+    self.reader.into_inner()
+  }
+}
+
+impl<T, R> Iterator for ReadAll<T, R>
+where
+  T: Read,
+  R: io::Read,
+{
+  type Item = io::Result<T>;
+
+  fn next(&mut self) -> Option<Self::Item>
+  {
+    if self.done {
+      return None;
+    }
+    let start = self.reader.count();
+    match T::read(&mut self.reader) {
+      Ok(record) => Some(Ok(record)),
+      // Nothing of the record was read, so the source ended between two records.
+      Err(err) if err.kind() == io::ErrorKind::UnexpectedEof && self.reader.count() == start => {
+        self.done = true;
+        None
+      }
+      Err(err) => {
+        self.done = true;
+        Some(Err(err))
+      }
+    }
+  }
+}
+
+/// Reader that counts the bytes that are read through it, e.g. to know the position of the data
+/// that is read next without requiring [`std::io::Seek`]. When the inner reader is seeked through
+/// it, the count moves by as many bytes as the inner reader does.
+///
+/// ```
+/// use std::io::{Cursor, Read};
+/// use structurs::{CountingReader, Reader};
+///
+/// let mut reader = CountingReader::new(Cursor::new(vec![1, 0, 2, 3, 4]));
+/// assert_eq!(1, reader.read_le::<u16>().unwrap());
+/// assert_eq!(2, reader.count());
+///
+/// // A read that returns fewer bytes than requested only counts the bytes that were read.
+/// let mut buf = [0u8; 8];
+/// assert_eq!(3, reader.read(&mut buf).unwrap());
+/// assert_eq!(5, reader.count());
+/// assert_eq!(0, reader.read(&mut buf).unwrap());
+/// assert_eq!(5, reader.count());
+/// ```
+pub struct CountingReader<R>
+{
+  inner: R,
+  count: u64,
+}
+
+impl<R> CountingReader<R>
+{
+  pub fn new(inner: R) -> Self
+  {
+    Self { inner, count: 0 }
+  }
+
+  /// Returns the number of bytes that have been read so far.
+  pub fn count(&self) -> u64
+  {
+    self.count
+  }
+
+  pub fn get_ref(&self) -> &R
+  {
+    &self.inner
+  }
+
+  pub fn get_mut(&mut self) -> &mut R
+  {
+    &mut self.inner
+  }
+
+  pub fn into_inner(self) -> R
+  {
+    self.inner
+  }
+}
+
+impl<R> io::Read for CountingReader<R>
+where
+  R: io::Read,
+{
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+  {
+    let read = self.inner.read(buf)?;
+    self.count += read as u64;
+    Ok(read)
+  }
+}
+
+impl<S> io::Seek for CountingReader<S>
+where
+  S: io::Seek,
+{
+  fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64>
+  {
+    let before = self.inner.stream_position()?;
+    let after = self.inner.seek(pos)?;
+    self.count = (self.count + after).wrapping_sub(before);
+    Ok(after)
+  }
+}
+
+/// Reader that can look at the bytes that come next without consuming them, e.g. to read a tag and
+/// decide which type to read. The bytes that are peeked are kept until they are read.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{PeekReader, Reader};
+///
+/// #[derive(structurs::Read, Debug, PartialEq)]
+/// struct Point
+/// {
+///   tag: u8,
+///   #[le]
+///   x: u16,
+///   #[le]
+///   y: u16,
+/// }
+///
+/// #[derive(structurs::Read, Debug, PartialEq)]
+/// struct Label
+/// {
+///   tag: u8,
+///   #[length_prefix(u8)]
+///   text: String,
+/// }
+///
+/// let bytes = vec![1, 3, 0, 4, 0, 2, 2, b'h', b'i'];
+/// let mut reader = PeekReader::new(Cursor::new(bytes));
+/// let mut points = Vec::new();
+/// let mut labels = Vec::new();
+/// for _ in 0..2 {
+///   match reader.peek_u8().unwrap() {
+///     1 => points.push(reader.read_as::<Point>().unwrap()),
+///     2 => labels.push(reader.read_as::<Label>().unwrap()),
+///     tag => panic!("unknown tag {}", tag),
+///   }
+/// }
+/// assert_eq!(vec![Point { tag: 1, x: 3, y: 4 }], points);
+/// assert_eq!("hi", labels[0].text);
+///
+/// // Peeking past the end fails without consuming anything.
+/// let mut reader = PeekReader::new(Cursor::new(vec![1, 2]));
+/// assert_eq!([1, 2], reader.peek_bytes(2).unwrap());
+/// assert!(reader.peek_bytes(3).is_err());
+/// assert_eq!(0x0201, reader.read_le::<u16>().unwrap());
+/// ```
+pub struct PeekReader<R>
+{
+  inner: R,
+  buf: Vec<u8>,
+  pos: usize,
+}
+
+impl<R> PeekReader<R>
+{
+  pub fn new(inner: R) -> Self
+  {
+    Self {
+      inner,
+      buf: Vec::new(),
+      pos: 0,
+    }
+  }
+
+  pub fn get_ref(&self) -> &R
+  {
+    &self.inner
+  }
+
+  /// Returns the inner reader. The bytes that were peeked but not read are lost.
+  pub fn into_inner(self) -> R
+  {
+    self.inner
+  }
+}
+
+impl<R> PeekReader<R>
+where
+  R: io::Read,
+{
+  /// Returns the next byte without consuming it.
+  pub fn peek_u8(&mut self) -> io::Result<u8>
+  {
+    Ok(self.peek_bytes(1)?[0])
+  }
+
+  /// Returns the next `n` bytes without consuming them. Fails with `UnexpectedEof` if the source
+  /// ends before them, in which case the bytes that are there can still be read.
+  pub fn peek_bytes(&mut self, n: usize) -> io::Result<&[u8]>
+  {
+    if self.pos > 0 {
+      self.buf.drain(..self.pos);
+      self.pos = 0;
+    }
+    while self.buf.len() < n {
+      let len = self.buf.len();
+      self.buf.resize(n, 0);
+      let result = self.inner.read(&mut self.buf[len..]);
+      match result {
+        Ok(read) => self.buf.truncate(len + read),
+        Err(_) => self.buf.truncate(len),
+      }
+      match result {
+        Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "failed to peek the bytes")),
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Err(err) => return Err(err),
+      }
+    }
+    Ok(&self.buf[..n])
+  }
+}
+
+impl<R> io::Read for PeekReader<R>
+where
+  R: io::Read,
+{
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+  {
+    if self.pos == self.buf.len() {
+      return self.inner.read(buf);
+    }
+    let read = buf.len().min(self.buf.len() - self.pos);
+    buf[..read].copy_from_slice(&self.buf[self.pos..self.pos + read]);
+    self.pos += read;
+    if self.pos == self.buf.len() {
+      self.buf.clear();
+      self.pos = 0;
+    }
+    Ok(read)
+  }
 }
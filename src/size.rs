@@ -0,0 +1,47 @@
+/// This trait gives the number of bytes a data type takes when it is read or written. It can only
+/// be implemented by data types that always take the same number of bytes.
+///
+/// ```
+/// use structurs::ByteSize;
+///
+/// #[derive(structurs::ByteSize)]
+/// struct Header
+/// {
+///   magic: [u8; 4],
+///   version: u16,
+///   #[pad(bytes = 2)]
+///   reserved: structurs::Pad,
+///   size: u64,
+///   flags: u32,
+/// }
+///
+/// const HEADER_SIZE: usize = Header::SIZE;
+/// assert_eq!(20, HEADER_SIZE);
+///
+/// assert_eq!(
+///   &[
+///     ("magic", 0, 4),
+///     ("version", 4, 2),
+///     ("reserved", 6, 2),
+///     ("size", 8, 8),
+///     ("flags", 16, 4)
+///   ],
+///   Header::LAYOUT
+/// );
+/// ```
+pub trait ByteSize
+{
+  /// Number of bytes the data type takes.
+  const SIZE: usize;
+
+  /// Name, offset and size in bytes of each field of a derived struct, in the order they are
+  /// stored. Fields without bytes are left out. Other data types don't have fields.
+  const LAYOUT: &'static [(&'static str, usize, usize)] = &[];
+}
+
+impl<T, const N: usize> ByteSize for [T; N]
+where
+  T: ByteSize,
+{
+  const SIZE: usize = T::SIZE * N;
+}
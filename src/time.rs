@@ -0,0 +1,225 @@
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::convert::TryFrom;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{io, ByteSize, Endian, Read, Write};
+
+/// Number of nanoseconds in a second.
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// Creates a duration from the seconds and the nanoseconds that were read.
+fn duration(secs: u64, nanos: u32) -> io::Result<Duration>
+{
+  if nanos >= NANOS_PER_SEC {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      alloc::format!("{} nanoseconds of a duration are not less than a second", nanos),
+    ));
+  }
+  Ok(Duration::new(secs, nanos))
+}
+
+/// `Duration` is stored as its whole seconds in a `u64` followed by its nanoseconds in a `u32`,
+/// both with the endian of the field. Reading nanoseconds that are not less than a second fails
+/// with [`std::io::ErrorKind::InvalidData`].
+///
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use std::time::Duration;
+/// use structurs::{Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Span
+/// {
+///   #[le]
+///   elapsed: Duration,
+/// }
+///
+/// let span = Span {
+///   elapsed: Duration::new(2, 500),
+/// };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&span).unwrap();
+/// assert_eq!(vec![2, 0, 0, 0, 0, 0, 0, 0, 0xF4, 1, 0, 0], *c.get_ref());
+///
+/// c.set_position(0);
+/// assert_eq!(span, c.read_as::<Span>().unwrap());
+///
+/// // 1_000_000_000 nanoseconds.
+/// let mut c = Cursor::new(vec![0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0xCA, 0x9A, 0x3B]);
+/// let err = c.read_as::<Span>().unwrap_err();
+/// assert_eq!(ErrorKind::InvalidData, err.kind());
+/// ```
+impl Read for Duration
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    duration(u64::read(reader)?, u32::read(reader)?)
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    duration(u64::read_endian(reader, endian)?, u32::read_endian(reader, endian)?)
+  }
+}
+
+impl Write for Duration
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.as_secs().write(writer)?;
+    self.subsec_nanos().write(writer)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    self.as_secs().write_endian(writer, endian)?;
+    self.subsec_nanos().write_endian(writer, endian)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+impl ByteSize for Duration
+{
+  const SIZE: usize = 12;
+}
+
+/// Creates a time from the seconds since the Unix epoch that were read.
+#[cfg(feature = "std")]
+fn system_time(secs: i64) -> io::Result<SystemTime>
+{
+  let offset = Duration::from_secs(secs.unsigned_abs());
+  let time = if secs >= 0 {
+    UNIX_EPOCH.checked_add(offset)
+  } else {
+    UNIX_EPOCH.checked_sub(offset)
+  };
+  time.ok_or_else(|| {
+    io::Error::new(
+      io::ErrorKind::InvalidData,
+      alloc::format!("{} seconds from the Unix epoch can't be represented as a time", secs),
+    )
+  })
+}
+
+/// Returns the whole seconds from the Unix epoch to the time, rounded down for times before the
+/// epoch.
+#[cfg(feature = "std")]
+fn unix_secs(time: &SystemTime) -> io::Result<i64>
+{
+  let secs = match time.duration_since(UNIX_EPOCH) {
+    Ok(after) => i64::try_from(after.as_secs()).ok(),
+    Err(err) => {
+      let before = err.duration();
+      let secs = before.as_secs() + u64::from(before.subsec_nanos() > 0);
+      i64::try_from(secs).ok().map(|secs| -secs)
+    }
+  };
+  secs.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "time is too far from the Unix epoch"))
+}
+
+/// `SystemTime` is stored as the whole seconds from the Unix epoch in an `i64` with the endian of
+/// the field, so times before 1970 are negative. Parts of a second are not stored, times are
+/// rounded down to the second before them.
+///
+/// ```
+/// use std::io::Cursor;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+/// use structurs::{Reader, Writer};
+///
+/// #[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+/// struct Metadata
+/// {
+///   #[be]
+///   modified: SystemTime,
+///   #[le]
+///   created: SystemTime,
+/// }
+///
+/// let metadata = Metadata {
+///   modified: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+///   created: UNIX_EPOCH - Duration::from_secs(86_400),
+/// };
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&metadata).unwrap();
+/// assert_eq!(&1_700_000_000i64.to_be_bytes(), &c.get_ref()[..8]);
+/// assert_eq!(&(-86_400i64).to_le_bytes(), &c.get_ref()[8..]);
+///
+/// c.set_position(0);
+/// assert_eq!(metadata, c.read_as::<Metadata>().unwrap());
+///
+/// // Half a second before the epoch is stored as a second before it.
+/// let mut c = Cursor::new(Vec::new());
+/// c.write_as(&(UNIX_EPOCH - Duration::from_millis(500))).unwrap();
+/// assert_eq!(&(-1i64).to_ne_bytes(), &c.get_ref()[..]);
+/// ```
+#[cfg(feature = "std")]
+impl Read for SystemTime
+{
+  #[inline]
+  fn read<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    system_time(i64::read(reader)?)
+  }
+
+  #[inline]
+  fn read_endian<R>(reader: &mut R, endian: Endian) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+  {
+    system_time(i64::read_endian(reader, endian)?)
+  }
+}
+
+#[cfg(feature = "std")]
+impl Write for SystemTime
+{
+  #[inline]
+  fn write<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    unix_secs(self)?.write(writer)
+  }
+
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+  {
+    unix_secs(self)?.write_endian(writer, endian)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    Self::SIZE
+  }
+}
+
+#[cfg(feature = "std")]
+impl ByteSize for SystemTime
+{
+  const SIZE: usize = 8;
+}
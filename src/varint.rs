@@ -0,0 +1,132 @@
+use crate::io;
+
+/// This trait can be used to read and write integers as variable length LEB128 integers, which use
+/// as few bytes as needed to store the value, 7 bits of it in each byte. Signed integers are zigzag
+/// encoded first, so numbers close to zero take few bytes regardless of their sign. Use
+/// `#[varint]` on a field to read and write it this way.
+///
+/// ```
+/// use std::io::Cursor;
+/// use structurs::{Reader, Varint, Writer};
+///
+/// fn encode<T: Varint>(value: T) -> Vec<u8>
+/// {
+///   let mut c = Cursor::new(Vec::new());
+///   c.write_leb128(&value).unwrap();
+///   c.into_inner()
+/// }
+///
+/// assert_eq!(vec![0x00], encode(0u32));
+/// assert_eq!(vec![0x7F], encode(127u32));
+/// assert_eq!(vec![0x80, 0x01], encode(128u32));
+/// assert_eq!(vec![0x03], encode(-2i32));
+///
+/// let bytes = encode(u64::MAX - 1);
+/// assert_eq!(10, bytes.len());
+/// assert_eq!(u64::MAX - 1, Cursor::new(bytes).read_leb128::<u64>().unwrap());
+/// assert_eq!(i64::MIN, Cursor::new(encode(i64::MIN)).read_leb128::<i64>().unwrap());
+///
+/// // Encodings with more bytes than the type can have are rejected, as are values that don't fit.
+/// let mut c = Cursor::new(vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+/// assert!(c.read_leb128::<u32>().is_err());
+/// let mut c = Cursor::new(vec![0x80, 0x02]);
+/// assert!(c.read_leb128::<u8>().is_err());
+/// ```
+pub trait Varint
+{
+  fn read_leb128<R>(reader: &mut R) -> io::Result<Self>
+  where
+    R: io::Read + ?Sized,
+    Self: Sized;
+
+  fn write_leb128<W>(&self, writer: &mut W) -> io::Result<()>
+  where
+    W: io::Write + ?Sized;
+}
+
+/// Reads an unsigned LEB128 integer that has at most `bits` bits.
+fn read_unsigned<R>(reader: &mut R, bits: u32) -> io::Result<u128>
+where
+  R: io::Read + ?Sized,
+{
+  let mut value = 0u128;
+  let mut shift = 0;
+  loop {
+    if shift >= bits {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "varint is too long"));
+    }
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    let low = u128::from(byte[0] & 0x7F);
+    if bits - shift < 7 && low >> (bits - shift) != 0 {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "varint doesn't fit into the type"));
+    }
+    value |= low << shift;
+    if byte[0] & 0x80 == 0 {
+      return Ok(value);
+    }
+    shift += 7;
+  }
+}
+
+fn write_unsigned<W>(writer: &mut W, mut value: u128) -> io::Result<()>
+where
+  W: io::Write + ?Sized,
+{
+  while value >= 0x80 {
+    writer.write_all(&[value as u8 | 0x80])?;
+    value >>= 7;
+  }
+  writer.write_all(&[value as u8])
+}
+
+macro_rules! impl_varint_unsigned {
+  ($($ty:ty),*) => {
+    $(
+      impl Varint for $ty
+      {
+        fn read_leb128<R>(reader: &mut R) -> io::Result<Self>
+        where
+          R: io::Read + ?Sized,
+        {
+          Ok(read_unsigned(reader, <$ty>::BITS)? as $ty)
+        }
+
+        fn write_leb128<W>(&self, writer: &mut W) -> io::Result<()>
+        where
+          W: io::Write + ?Sized,
+        {
+          write_unsigned(writer, *self as u128)
+        }
+      }
+    )*
+  };
+}
+
+macro_rules! impl_varint_signed {
+  ($($ty:ty => $unsigned:ty),*) => {
+    $(
+      impl Varint for $ty
+      {
+        fn read_leb128<R>(reader: &mut R) -> io::Result<Self>
+        where
+          R: io::Read + ?Sized,
+        {
+          let zigzag = read_unsigned(reader, <$ty>::BITS)? as $unsigned;
+          Ok((zigzag >> 1) as $ty ^ -((zigzag & 1) as $ty))
+        }
+
+        fn write_leb128<W>(&self, writer: &mut W) -> io::Result<()>
+        where
+          W: io::Write + ?Sized,
+        {
+          let zigzag = ((*self << 1) ^ (*self >> (<$ty>::BITS - 1))) as $unsigned;
+          write_unsigned(writer, zigzag as u128)
+        }
+      }
+    )*
+  };
+}
+
+impl_varint_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_varint_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
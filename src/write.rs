@@ -1,4 +1,6 @@
-use std::io;
+use alloc::vec::Vec;
+
+use crate::{io, Endian, Varint};
 
 pub trait Writer: io::Write
 {
@@ -28,27 +30,59 @@ pub trait Writer: io::Write
   {
     T::write_be(v, self)
   }
+
+  #[inline]
+  fn write_leb128<T>(&mut self, v: &T) -> io::Result<()>
+  where
+    T: Varint,
+    Self: Sized,
+  {
+    v.write_leb128(self)
+  }
+}
+
+impl<T> Writer for T where T: io::Write + ?Sized {}
+
+/// Writes `value` to `writer`, the same as `value.write(writer)`. See [`structurs::from_reader`].
+#[inline]
+pub fn to_writer<T, W>(value: &T, writer: &mut W) -> io::Result<()>
+where
+  T: Write,
+  W: io::Write + ?Sized,
+{
+  value.write(writer)
 }
 
-impl<T> Writer for T where T: io::Write {}
+/// Writes the records one after another, so they can be read back with [`structurs::read_all`].
+pub fn write_all<'a, T, W, I>(mut writer: W, records: I) -> io::Result<()>
+where
+  T: Write + 'a,
+  W: io::Write,
+  I: IntoIterator<Item = &'a T>,
+{
+  for record in records {
+    record.write(&mut writer)?;
+  }
+  Ok(())
+}
 
 pub trait PrimitiveWrite
 {
   fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
   where
-    W: io::Write,
+    W: io::Write + ?Sized,
     Self: Sized;
 
   fn write_be<W>(&self, writer: &mut W) -> io::Result<()>
   where
-    W: io::Write,
+    W: io::Write + ?Sized,
     Self: Sized;
 
   #[cfg(target_endian = "little")]
   #[inline]
   fn write_ne<W>(&self, writer: &mut W) -> io::Result<()>
   where
-    W: io::Write,
+    W: io::Write + ?Sized,
     Self: Sized,
   {
     Self::write_le(self, writer)
@@ -58,17 +92,271 @@ pub trait PrimitiveWrite
   #[inline]
   fn write_ne<W>(&self, writer: &mut W) -> io::Result<()>
   where
-    W: io::Write,
+    W: io::Write + ?Sized,
     Self: Sized,
   {
     Self::write_be(self, writer)
   }
 }
 
+/// The sink doesn't need to be sized, so a data type can be written to a trait object directly.
+///
+/// ```
+/// use std::io::Write;
+///
+/// #[derive(structurs::Write)]
+/// struct Pair
+/// {
+///   #[be]
+///   a: u16,
+///   b: [u8; 2],
+/// }
+///
+/// let mut bytes = Vec::new();
+/// let writer: &mut dyn Write = &mut bytes;
+/// structurs::Write::write(&Pair { a: 1, b: [2, 3] }, writer).unwrap();
+/// assert_eq!(vec![0, 1, 2, 3], bytes);
+/// ```
 pub trait Write
 {
   fn write<W>(&self, writer: &mut W) -> io::Result<()>
   where
-    W: io::Write,
+    W: io::Write + ?Sized,
     Self: Sized;
+
+  /// Writes the data type to a sink using `endian` for the parts of it that don't have a fixed
+  /// endian. Data types whose representation doesn't depend on the endian are written using
+  /// [`structurs::Write::write`].
+  #[inline]
+  fn write_endian<W>(&self, writer: &mut W, endian: Endian) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+    Self: Sized,
+  {
+    let _ = endian;
+    self.write(writer)
+  }
+
+  /// Writes the data type like [`structurs::Write::write_endian`] when `position` bytes of the
+  /// stream come before it. Derived implementations align their fields from the start of the
+  /// stream, so a nested struct is given the position of its field.
+  #[doc(hidden)]
+  #[inline]
+  fn write_endian_at<W>(&self, writer: &mut W, endian: Endian, position: u64) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+    Self: Sized,
+  {
+    let _ = position;
+    self.write_endian(writer, endian)
+  }
+
+  /// Writes the elements of a slice of the data type, using `endian` if it is given like
+  /// [`structurs::Write::write_endian`]. Primitive types override it to write the bytes of many
+  /// elements at once.
+  #[doc(hidden)]
+  #[inline]
+  fn write_slice<W>(slice: &[Self], writer: &mut W, endian: Option<Endian>) -> io::Result<()>
+  where
+    W: io::Write + ?Sized,
+    Self: Sized,
+  {
+    for elem in slice {
+      match endian {
+        Some(endian) => elem.write_endian(writer, endian)?,
+        None => elem.write(writer)?,
+      }
+    }
+    Ok(())
+  }
+
+  /// Writes the data type to a sink and returns the number of bytes that were written.
+  /// ```
+  /// use std::io::Cursor;
+  /// use structurs::Write;
+  ///
+  /// #[derive(structurs::Write)]
+  /// struct Test
+  /// {
+  ///   a: u32,
+  ///   #[pad(bytes = 3)]
+  ///   pad_field: structurs::Pad,
+  ///   #[length_prefix(u16)]
+  ///   b: Vec<u8>,
+  /// }
+  ///
+  /// fn main()
+  /// {
+  ///   let mut c: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+  ///   let test = Test {
+  ///     a: 1,
+  ///     pad_field: structurs::Pad,
+  ///     b: vec![1, 2, 3],
+  ///   };
+  ///   let written = test.write_counted(&mut c).unwrap();
+  ///   assert_eq!(12, written);
+  ///   assert_eq!(c.into_inner().len(), written);
+  /// }
+  /// ```
+  fn write_counted<W>(&self, writer: &mut W) -> io::Result<usize>
+  where
+    W: io::Write + ?Sized,
+    Self: Sized,
+  {
+    let mut writer = CountingWriter::new(writer);
+    self.write(&mut writer)?;
+    Ok(writer.count() as usize)
+  }
+
+  /// Returns the number of bytes that writing the data type is expected to take, which
+  /// [`structurs::Write::to_vec`] allocates up front. It is only a hint, so data types whose number
+  /// of bytes isn't known without writing them return fewer bytes, by default none. Data types that
+  /// implement [`structurs::ByteSize`] return its `SIZE`, and derived structs return the sum of the
+  /// hints of their fields.
+  ///
+  /// ```
+  /// use structurs::Write;
+  ///
+  /// #[derive(structurs::Write)]
+  /// struct Header
+  /// {
+  ///   magic: [u8; 4],
+  ///   #[be]
+  ///   version: u16,
+  ///   #[pad(bytes = 2)]
+  ///   reserved: structurs::Pad,
+  ///   #[length_prefix(u8)]
+  ///   name: String,
+  /// }
+  ///
+  /// let header = Header {
+  ///   magic: *b"HDR0",
+  ///   version: 1,
+  ///   reserved: structurs::Pad,
+  ///   name: "header".to_string(),
+  /// };
+  /// // The length of the name isn't counted, since it is only known when it is written.
+  /// assert_eq!(8, header.size_hint());
+  /// assert_eq!(15, header.to_vec().unwrap().len());
+  /// ```
+  #[inline]
+  fn size_hint(&self) -> usize
+  {
+    0
+  }
+
+  /// Writes the data type to a new `Vec` and returns it. The `Vec` is allocated with the capacity
+  /// given by [`structurs::Write::size_hint`].
+  ///
+  /// ```
+  /// use std::io::Cursor;
+  /// use structurs::Write;
+  ///
+  /// #[derive(structurs::Write)]
+  /// struct Packet
+  /// {
+  ///   #[be]
+  ///   id: u16,
+  ///   #[length_prefix(u8)]
+  ///   payload: Vec<u8>,
+  /// }
+  ///
+  /// let packet = Packet {
+  ///   id: 0x102,
+  ///   payload: vec![7, 8],
+  /// };
+  /// let mut c = Cursor::new(Vec::new());
+  /// packet.write(&mut c).unwrap();
+  /// assert_eq!(c.into_inner(), packet.to_vec().unwrap());
+  /// assert_eq!(vec![1, 2, 2, 7, 8], packet.to_vec().unwrap());
+  /// ```
+  #[inline]
+  fn to_vec(&self) -> io::Result<Vec<u8>>
+  where
+    Self: Sized,
+  {
+    let mut vec = Vec::with_capacity(self.size_hint());
+    self.write(&mut vec)?;
+    Ok(vec)
+  }
+}
+
+/// Writer that counts the bytes that are written through it, e.g. to know the position of the data
+/// that is written next without requiring [`std::io::Seek`].
+///
+/// ```
+/// use std::io::Write;
+/// use structurs::{CountingWriter, Writer};
+///
+/// let mut writer = CountingWriter::new(Vec::new());
+/// writer.write_le(&0x1234u16).unwrap();
+/// assert_eq!(2, writer.count());
+///
+/// // Only the bytes that the inner writer accepts are counted.
+/// let mut buf = [0u8; 3];
+/// let mut writer = CountingWriter::new(&mut buf[..]);
+/// assert_eq!(3, writer.write(&[1, 2, 3, 4, 5]).unwrap());
+/// assert_eq!(0, writer.write(&[6]).unwrap());
+/// assert_eq!(3, writer.count());
+/// ```
+pub struct CountingWriter<W>
+{
+  inner: W,
+  count: u64,
+}
+
+impl<W> CountingWriter<W>
+{
+  pub fn new(inner: W) -> Self
+  {
+    Self { inner, count: 0 }
+  }
+
+  /// Returns the number of bytes that have been written so far.
+  pub fn count(&self) -> u64
+  {
+    self.count
+  }
+
+  pub fn get_ref(&self) -> &W
+  {
+    &self.inner
+  }
+
+  pub fn get_mut(&mut self) -> &mut W
+  {
+    &mut self.inner
+  }
+
+  pub fn into_inner(self) -> W
+  {
+    self.inner
+  }
+}
+
+impl<W> io::Write for CountingWriter<W>
+where
+  W: io::Write,
+{
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+  {
+    let written = self.inner.write(buf)?;
+    self.count += written as u64;
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()>
+  {
+    self.inner.flush()
+  }
+}
+
+/// Number of bytes from `count` to the next multiple of `alignment`.
+pub(crate) fn padding(count: u64, alignment: usize) -> io::Result<usize>
+{
+  if alignment == 0 {
+    return Err(io::Error::new(io::ErrorKind::InvalidInput, "alignment can't be zero"));
+  }
+  let alignment = alignment as u64;
+  Ok(((alignment - count % alignment) % alignment) as usize)
 }
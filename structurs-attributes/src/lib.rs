@@ -0,0 +1,641 @@
+//! Parser of the attributes of the `structurs` derive macros, so that other derive macros can
+//! accept the same attributes without parsing them again. The attributes of a field are parsed by
+//! [`Attributes::from_field`], and the attributes of a struct or an enum by [`Attributes::new`].
+//!
+//! ```
+//! use structurs_attributes::{Attributes, Endian, Padding, PaddingSize};
+//!
+//! let source = "#[be] #[pad(bytes = 4)] reserved: Pad";
+//! let field = syn::parse::Parser::parse_str(syn::Field::parse_named, source).unwrap();
+//! let attrs = Attributes::from_field(&field).unwrap();
+//! assert!(matches!(attrs.endian, Endian::Big));
+//! assert!(matches!(attrs.padding, Some(Padding { size: PaddingSize::Bytes(4), .. })));
+//! ```
+//!
+//! Every endian attribute has a short and a long form, and can be given on its own or in a
+//! `structurs` group. Only one of them can be given.
+//!
+//! ```
+//! use structurs_attributes::{Attributes, Endian};
+//!
+//! fn endian(source: &str) -> Endian
+//! {
+//!   let field = syn::parse::Parser::parse_str(syn::Field::parse_named, source).unwrap();
+//!   Attributes::from_field(&field).unwrap().endian
+//! }
+//!
+//! assert!(matches!(endian("value: u32"), Endian::Normal));
+//! assert!(matches!(endian("#[le] value: u32"), Endian::Little));
+//! assert!(matches!(endian("#[little_endian] value: u32"), Endian::Little));
+//! assert!(matches!(endian("#[be] value: u32"), Endian::Big));
+//! assert!(matches!(endian("#[big_endian] value: u32"), Endian::Big));
+//! assert!(matches!(endian("#[ne] value: u32"), Endian::Native));
+//! assert!(matches!(endian("#[native_endian] value: u32"), Endian::Native));
+//! assert!(matches!(endian("#[structurs(be)] value: u32"), Endian::Big));
+//! assert!(matches!(endian("#[endian(order)] value: u32"), Endian::Expr(_)));
+//!
+//! let conflicts = [
+//!   "#[le] #[be] value: u32",
+//!   "#[le] #[structurs(le)] value: u32",
+//!   "#[big_endian] #[endian(order)] value: u32",
+//! ];
+//! for source in &conflicts {
+//!   let field = syn::parse::Parser::parse_str(syn::Field::parse_named, source).unwrap();
+//!   assert!(Attributes::from_field(&field).is_err());
+//! }
+//! ```
+//!
+//! Padding is given by `pad`, `reserved`, `pad_before` and `pad_after`, with any of `bytes = N` or
+//! `align = N`, `fill = B` and `strict`.
+//!
+//! ```
+//! use structurs_attributes::{Attributes, Padding, PaddingSize};
+//!
+//! fn parse_field(source: &str) -> syn::Field
+//! {
+//!   syn::parse::Parser::parse_str(syn::Field::parse_named, source).unwrap()
+//! }
+//!
+//! let field = parse_field("#[pad] reserved: u16");
+//! let padding = Attributes::from_field(&field).unwrap().padding.unwrap();
+//! assert!(matches!(padding, Padding { size: PaddingSize::Normal, fill: None, strict: false }));
+//!
+//! let field = parse_field("#[pad(bytes = 3, fill = 0xFF, strict)] reserved: Pad");
+//! let padding = Attributes::from_field(&field).unwrap().padding.unwrap();
+//! assert!(matches!(padding.size, PaddingSize::Bytes(3)));
+//! assert!(padding.fill.is_some() && padding.strict);
+//!
+//! let field = parse_field("#[structurs(pad(align = 8))] reserved: Pad");
+//! let attrs = Attributes::from_field(&field).unwrap();
+//! assert!(Padding::is_aligned(&attrs.padding));
+//!
+//! let field = parse_field("#[reserved(bytes = 2)] reserved: Pad");
+//! let padding = Attributes::from_field(&field).unwrap().padding.unwrap();
+//! assert!(matches!(padding, Padding { size: PaddingSize::Bytes(2), fill: None, strict: true }));
+//!
+//! let field = parse_field("#[pad_before(bytes = 2)] #[pad_after(align = 4)] value: u8");
+//! let attrs = Attributes::from_field(&field).unwrap();
+//! assert!(attrs.padding.is_none());
+//! assert!(matches!(attrs.pad_before, Some(Padding { size: PaddingSize::Bytes(2), .. })));
+//! assert!(Padding::is_aligned(&attrs.pad_after));
+//!
+//! // Padding around a field needs its size, reserved bytes are always zero, only one size can be
+//! // given and a field is only one padding.
+//! let invalid = [
+//!   "#[pad] #[reserved] reserved: Pad",
+//!   "#[pad_before] value: u8",
+//!   "#[reserved(fill = 1)] reserved: Pad",
+//!   "#[pad(bytes = 2, align = 4)] reserved: Pad",
+//!   "#[pad(byte = 4)] reserved: Pad",
+//! ];
+//! for source in &invalid {
+//!   assert!(Attributes::from_field(&parse_field(source)).is_err());
+//! }
+//! ```
+//!
+//! The other attributes take a value, either as `#[name = value]` for literals or as
+//! `#[name(value)]`, or are flags without one.
+//!
+//! ```
+//! use structurs_attributes::{Attributes, Endian};
+//!
+//! fn parse_field(source: &str) -> syn::Field
+//! {
+//!   syn::parse::Parser::parse_str(syn::Field::parse_named, source).unwrap()
+//! }
+//!
+//! let field = parse_field(
+//!   "#[length_prefix(u32, le)] #[max = 16] #[structurs(if = flags != 0, utf16)] name: String",
+//! );
+//! let attrs = Attributes::from_field(&field).unwrap();
+//! let length_prefix = attrs.length_prefix.unwrap();
+//! assert!(matches!(length_prefix.endian, Endian::Little));
+//! assert!(matches!(length_prefix.endian(&Endian::Big), Endian::Little));
+//! assert!(attrs.max.is_some() && attrs.condition.is_some() && attrs.utf16);
+//!
+//! let field = parse_field("#[count(length)] #[varint] values: Vec<u32>");
+//! let attrs = Attributes::from_field(&field).unwrap();
+//! assert!(attrs.count.is_some() && attrs.varint);
+//! assert_eq!(Some("varint"), attrs.blocking());
+//!
+//! let field = parse_field("#[fixed_point(u16, frac = 8)] gain: f32");
+//! let fixed_point = Attributes::from_field(&field).unwrap().fixed_point.unwrap();
+//! assert_eq!(8, fixed_point.frac.base10_parse::<u32>().unwrap());
+//!
+//! // `PhantomData` fields don't have any bytes.
+//! let field = parse_field("marker: PhantomData<T>");
+//! assert!(Attributes::from_field(&field).unwrap().skip);
+//!
+//! // Attributes of other macros are left alone, while unknown attributes in a group are errors.
+//! let field = parse_field("#[serde(rename = \"id\")] #[doc = \"Id.\"] id: u32");
+//! assert!(Attributes::from_field(&field).is_ok());
+//! let field = parse_field("#[structurs(unknown)] id: u32");
+//! assert!(Attributes::from_field(&field).is_err());
+//! ```
+
+/// Endian attribute value.
+#[derive(Debug, Default)]
+pub enum Endian
+{
+  /// `le` or `little_endian`.
+  Little,
+  /// `be` or `big_endian`.
+  Big,
+  /// `ne` or `native_endian`.
+  Native,
+  /// Expression over the previous fields that gives the endian at runtime.
+  Expr(Box<syn::Expr>),
+  /// No endian attribute was given, the endian the struct is read or written with is used.
+  #[default]
+  Normal,
+}
+
+/// Number of bytes of a padding.
+#[derive(Debug, Default)]
+pub enum PaddingSize
+{
+  /// As many bytes as the type of the field takes.
+  #[default]
+  Normal,
+  /// N bytes, given by `bytes = N`.
+  Bytes(usize),
+  /// Pads up to a multiple of N bytes from the start of the struct.
+  Align(Box<syn::Expr>),
+}
+
+/// Padding attribute value.
+#[derive(Debug, Default)]
+pub struct Padding
+{
+  /// Number of bytes, given by `bytes = N` or `align = N`.
+  pub size: PaddingSize,
+  /// Byte that the padding is written with, zero by default.
+  pub fill: Option<syn::Expr>,
+  /// Whether reading checks that the padding is made of the fill byte.
+  pub strict: bool,
+}
+
+impl Padding
+{
+  /// Parses the arguments of a padding attribute, which are either nothing or any of `bytes = N` or
+  /// `align = N`, `fill = B` and `strict` separated by commas.
+  pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Self>
+  {
+    let mut padding = Padding::default();
+    if !input.peek(syn::token::Paren) {
+      return Ok(padding);
+    }
+    let content;
+    syn::parenthesized!(content in input);
+    while !content.is_empty() {
+      let ident: syn::Ident = content.parse()?;
+      if ident == "bytes" || ident == "align" {
+        if !matches!(padding.size, PaddingSize::Normal) {
+          return Err(syn::Error::new_spanned(ident, "only one of `bytes` and `align` can be given"));
+        }
+        content.parse::<syn::Token![=]>()?;
+        padding.size = if ident == "bytes" {
+          let length: syn::LitInt = content.parse()?;
+          PaddingSize::Bytes(length.base10_parse()?)
+        } else {
+          PaddingSize::Align(Box::new(content.parse()?))
+        };
+      } else if ident == "fill" {
+        content.parse::<syn::Token![=]>()?;
+        padding.fill = Some(content.parse()?);
+      } else if ident == "strict" {
+        padding.strict = true;
+      } else {
+        return Err(syn::Error::new_spanned(ident, "expected `bytes`, `align`, `fill` or `strict`"));
+      }
+      if !content.is_empty() {
+        content.parse::<syn::Token![,]>()?;
+      }
+    }
+    Ok(padding)
+  }
+
+  /// Parses the arguments of a reserved field, which is padding that must be zero.
+  pub fn parse_reserved(name: &syn::Ident, input: syn::parse::ParseStream) -> syn::Result<Self>
+  {
+    let padding = Self::parse(input)?;
+    if padding.fill.is_some() {
+      return Err(syn::Error::new_spanned(
+        name,
+        "'reserved' attribute is always zero, use `pad(fill = B, strict)` for another byte.",
+      ));
+    }
+    Ok(Self { strict: true, ..padding })
+  }
+
+  /// Parses the arguments of a padding around a field, which needs either `bytes = N` or
+  /// `align = N` since the field doesn't tell its size.
+  pub fn parse_around(name: &syn::Ident, input: syn::parse::ParseStream) -> syn::Result<Self>
+  {
+    let padding = Self::parse(input)?;
+    match padding.size {
+      PaddingSize::Normal => Err(syn::Error::new_spanned(
+        name,
+        format!("'{}' attribute needs `bytes = N` or `align = N`.", name),
+      )),
+      _ => Ok(padding),
+    }
+  }
+
+  /// Returns whether the padding, if there is one, pads up to a multiple of bytes.
+  pub fn is_aligned(padding: &Option<Self>) -> bool
+  {
+    matches!(padding, Some(Padding { size: PaddingSize::Align(_), .. }))
+  }
+}
+
+/// Length prefix attribute value.
+#[derive(Debug)]
+pub struct LengthPrefix
+{
+  /// Integer type of the length.
+  pub ty: syn::Type,
+  /// Endian of the length, which is the endian of the field if it isn't given.
+  pub endian: Endian,
+}
+
+impl syn::parse::Parse for LengthPrefix
+{
+  /// Parses the type of the length, optionally followed by its endian, e.g. `u32` or `u32, le`.
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self>
+  {
+    let ty = input.parse()?;
+    let mut endian = Endian::Normal;
+    if input.peek(syn::Token![,]) {
+      input.parse::<syn::Token![,]>()?;
+      let ident: syn::Ident = input.parse()?;
+      endian = match ident.to_string().as_str() {
+        "le" | "little_endian" => Endian::Little,
+        "be" | "big_endian" => Endian::Big,
+        "ne" | "native_endian" => Endian::Native,
+        _ => return Err(syn::Error::new_spanned(ident, "expected `le`, `be` or `ne`")),
+      };
+    }
+    Ok(Self { ty, endian })
+  }
+}
+
+impl LengthPrefix
+{
+  /// Returns the endian of the length, given the endian of the field.
+  pub fn endian<'a>(&'a self, field: &'a Endian) -> &'a Endian
+  {
+    match self.endian {
+      Endian::Normal => field,
+      ref endian => endian,
+    }
+  }
+}
+
+/// Fixed-point attribute value.
+#[derive(Debug)]
+pub struct FixedPoint
+{
+  /// Integer type that the number is stored as.
+  pub ty: syn::Type,
+  /// Number of bits of the integer that come after the point.
+  pub frac: syn::LitInt,
+}
+
+impl syn::parse::Parse for FixedPoint
+{
+  /// Parses the number of fractional bits, optionally preceded by the integer type, e.g.
+  /// `frac = 16` or `u32, frac = 16`. The integer type is `i32` if it isn't given.
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self>
+  {
+    let ty = if input.peek(syn::Ident) && input.peek2(syn::Token![=]) {
+      syn::parse_quote! { i32 }
+    } else {
+      let ty = input.parse()?;
+      input.parse::<syn::Token![,]>()?;
+      ty
+    };
+    let ident: syn::Ident = input.parse()?;
+    if ident != "frac" {
+      return Err(syn::Error::new_spanned(ident, "expected `frac`"));
+    }
+    input.parse::<syn::Token![=]>()?;
+    let frac: syn::LitInt = input.parse()?;
+    if frac.base10_parse::<u32>()? >= 128 {
+      return Err(syn::Error::new_spanned(
+        frac,
+        "'fixed_point' attribute can't have more than 127 fractional bits.",
+      ));
+    }
+    Ok(Self { ty, frac })
+  }
+}
+
+/// Bit order attribute value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder
+{
+  /// `msb`, the first bit field takes the most significant bits of its integer.
+  #[default]
+  Msb,
+  /// `lsb`, the first bit field takes the least significant bits of its integer.
+  Lsb,
+}
+
+impl syn::parse::Parse for BitOrder
+{
+  fn parse(input: syn::parse::ParseStream) -> syn::Result<Self>
+  {
+    let ident: syn::Ident = input.parse()?;
+    match ident.to_string().as_str() {
+      "msb" => Ok(BitOrder::Msb),
+      "lsb" => Ok(BitOrder::Lsb),
+      _ => Err(syn::Error::new_spanned(ident, "expected `msb` or `lsb`")),
+    }
+  }
+}
+
+/// Attributes of a struct, an enum or a field.
+#[derive(Debug, Default)]
+pub struct Attributes
+{
+  /// Endian of the field, or the default endian of the fields of a struct.
+  pub endian: Endian,
+  /// Padding that the field is, given by `pad` or `reserved`.
+  pub padding: Option<Padding>,
+  /// Padding before the field, which is still read and written.
+  pub pad_before: Option<Padding>,
+  /// Padding after the field, which is still read and written.
+  pub pad_after: Option<Padding>,
+  /// Whether the field is left out of the bytes.
+  pub skip: bool,
+  /// Value of a padding or skipped field, instead of its default value.
+  pub default: Option<syn::Expr>,
+  /// Bytes that a `[u8; N]` field must always have.
+  pub magic: Option<syn::LitByteStr>,
+  /// Value that the field must have after it is read.
+  pub assert_eq: Option<syn::Expr>,
+  /// Type of the length that precedes the elements of a `Vec<T>` field.
+  pub length_prefix: Option<LengthPrefix>,
+  /// Expression over the previous fields that gives the number of elements of a `Vec<T>` field.
+  pub count: Option<syn::Expr>,
+  /// Expression over the previous fields that gives the number of bytes of the elements of a
+  /// `Vec<T>` field.
+  pub count_bytes: Option<syn::Expr>,
+  /// Whether a `String` or `Vec<u8>` field is terminated by a NUL byte.
+  pub cstring: bool,
+  /// Width in bytes of a NUL padded `String` field.
+  pub fixed: Option<syn::Expr>,
+  /// Expression over the previous fields that tells whether an `Option<T>` field is present.
+  pub present_if: Option<syn::Expr>,
+  /// Boolean expression over the previous fields that tells whether the field is read and written.
+  pub condition: Option<syn::Expr>,
+  /// Expression over the previous fields that gives the position the field is read from.
+  pub offset: Option<syn::Expr>,
+  /// Multiple of bytes from the start of the stream that the field starts at.
+  pub align: Option<syn::Expr>,
+  /// Function that reads the field instead of its `Read` implementation.
+  pub read_with: Option<syn::Path>,
+  /// Function that writes the field instead of its `Write` implementation.
+  pub write_with: Option<syn::Path>,
+  /// Whether an integer field is a LEB128 varint.
+  pub varint: bool,
+  /// Integer type of the tag that selects the variant of an enum.
+  pub tag: Option<syn::Type>,
+  /// Number of bytes that the `ByteSize` of a struct must be.
+  pub size: Option<syn::Expr>,
+  /// Whether a `u32` field is the CRC32 of the bytes of the struct before it.
+  pub crc32: bool,
+  /// Hasher whose hash of the bytes of the struct before a field is the value of the field.
+  pub digest: Option<syn::Type>,
+  /// Whether a `String` field is stored as UTF-16 code units instead of UTF-8 bytes.
+  pub utf16: bool,
+  /// Whether a `Vec<u8>` field is the rest of the bytes of the source.
+  pub rest: bool,
+  /// Byte that ends a `Vec<u8>` or `String` field, like the NUL byte of `cstring`.
+  pub terminator: Option<syn::Expr>,
+  /// Constant number of elements of a `Vec<T>` field.
+  pub repeat: Option<syn::Expr>,
+  /// Largest number of elements that a `Vec<T>` or `String` field with a length can have.
+  pub max: Option<syn::Expr>,
+  /// Number of bits of an integer that the field takes, together with the fields around it.
+  pub bits: Option<syn::LitInt>,
+  /// Order that the bit fields take the bits of their integer in.
+  pub bit_order: Option<BitOrder>,
+  /// Whether reading a struct must take exactly the number of bytes of its `ByteSize`.
+  pub exact_size: bool,
+  /// Integer that a float field is stored as, and the number of its bits after the point.
+  pub fixed_point: Option<FixedPoint>,
+}
+
+impl Attributes
+{
+  /// Returns the name of an attribute that needs blocking IO, so it can't be used asynchronously.
+  /// The docs of `structurs::AsyncRead` list the same attributes.
+  pub fn blocking(&self) -> Option<&'static str>
+  {
+    if self.cstring {
+      Some("cstring")
+    } else if self.terminator.is_some() {
+      Some("terminator")
+    } else if Padding::is_aligned(&self.padding) {
+      Some("pad(align)")
+    } else if Padding::is_aligned(&self.pad_before) {
+      Some("pad_before(align)")
+    } else if Padding::is_aligned(&self.pad_after) {
+      Some("pad_after(align)")
+    } else if self.fixed.is_some() {
+      Some("fixed")
+    } else if self.offset.is_some() {
+      Some("offset")
+    } else if self.align.is_some() {
+      Some("align")
+    } else if self.read_with.is_some() {
+      Some("read_with")
+    } else if self.write_with.is_some() {
+      Some("write_with")
+    } else if self.varint {
+      Some("varint")
+    } else if self.crc32 {
+      Some("crc32")
+    } else if self.digest.is_some() {
+      Some("digest")
+    } else {
+      None
+    }
+  }
+
+  /// Reads the attributes of a struct or a field. Attributes can either be used on their own, e.g.
+  /// `#[be] #[pad(bytes = 4)]`, or grouped together, e.g. `#[structurs(be, pad(bytes = 4))]`. Each
+  /// attribute can only be given once, and only one of the attributes that set the same thing, like
+  /// the endian attributes, can be given.
+  pub fn new(attrs: &[syn::Attribute]) -> syn::Result<Self>
+  {
+    Self::parse(attrs).map(|(attributes, _)| attributes)
+  }
+
+  /// Reads the attributes of a field. `PhantomData` fields don't have any bytes, so they are
+  /// always skipped. Attributes that only apply to a struct or an enum as a whole can't be used.
+  pub fn from_field(field: &syn::Field) -> syn::Result<Self>
+  {
+    let (mut attributes, given) = Self::parse(&field.attrs)?;
+    if let Some(name) = given.iter().find(|name| CONTAINER_ONLY.iter().any(|only| *name == only)) {
+      return Err(syn::Error::new_spanned(
+        name,
+        format!("attribute `{}` can only be used on a struct or an enum, not on a field", name),
+      ));
+    }
+    attributes.skip |= is_phantom_type(&field.ty);
+    Ok(attributes)
+  }
+
+  /// Reads the attributes and returns them together with the names of the ones that were given.
+  fn parse(attrs: &[syn::Attribute]) -> syn::Result<(Self, Vec<syn::Ident>)>
+  {
+    let mut attributes = Self::default();
+    let mut given: Vec<syn::Ident> = Vec::new();
+    for attr in attrs {
+      let ident = match attr.path.get_ident() {
+        Some(ident) => ident,
+        None => continue,
+      };
+      if ident == "structurs" {
+        attr.parse_args_with(|input: syn::parse::ParseStream| {
+          while !input.is_empty() {
+            // `if` is a keyword, so it can only be used as an attribute inside of the group.
+            let name = syn::ext::IdentExt::parse_any(input)?;
+            if !attributes.parse_directive(&name, input)? {
+              return Err(syn::Error::new_spanned(&name, format!("unknown attribute `{}`", name)));
+            }
+            check_conflict(&given, &name)?;
+            given.push(name);
+            if !input.is_empty() {
+              input.parse::<syn::Token![,]>()?;
+            }
+          }
+          Ok(())
+        })?;
+      } else {
+        let parser = |input: syn::parse::ParseStream| attributes.parse_directive(ident, input);
+        if syn::parse::Parser::parse2(parser, attr.tokens.clone())? {
+          check_conflict(&given, ident)?;
+          given.push(ident.clone());
+        }
+      }
+    }
+    Ok((attributes, given))
+  }
+
+  /// Parses the arguments of the attribute with the given name. Returns whether the name is a known
+  /// attribute.
+  pub fn parse_directive(&mut self, name: &syn::Ident, input: syn::parse::ParseStream) -> syn::Result<bool>
+  {
+    match name.to_string().as_str() {
+      "le" | "little_endian" => self.endian = Endian::Little,
+      "be" | "big_endian" => self.endian = Endian::Big,
+      "ne" | "native_endian" => self.endian = Endian::Native,
+      "endian" => self.endian = Endian::Expr(parse_value(input)?),
+      "pad" => self.padding = Some(Padding::parse(input)?),
+      "reserved" => self.padding = Some(Padding::parse_reserved(name, input)?),
+      "pad_before" => self.pad_before = Some(Padding::parse_around(name, input)?),
+      "pad_after" => self.pad_after = Some(Padding::parse_around(name, input)?),
+      "skip" => self.skip = true,
+      "default" => self.default = Some(parse_value(input)?),
+      "magic" => self.magic = Some(parse_value(input)?),
+      "assert_eq" => self.assert_eq = Some(parse_value(input)?),
+      "length_prefix" => self.length_prefix = Some(parse_value(input)?),
+      "count" => self.count = Some(parse_value(input)?),
+      "count_bytes" => self.count_bytes = Some(parse_value(input)?),
+      "cstring" => self.cstring = true,
+      "fixed" => self.fixed = Some(parse_value(input)?),
+      "present_if" => self.present_if = Some(parse_value(input)?),
+      "if" => self.condition = Some(parse_value(input)?),
+      "offset" => self.offset = Some(parse_value(input)?),
+      "align" => self.align = Some(parse_value(input)?),
+      "read_with" => self.read_with = Some(parse_value(input)?),
+      "write_with" => self.write_with = Some(parse_value(input)?),
+      "varint" => self.varint = true,
+      "tag" => self.tag = Some(parse_value(input)?),
+      "size" => self.size = Some(parse_value(input)?),
+      "crc32" => self.crc32 = true,
+      "digest" => self.digest = Some(parse_value(input)?),
+      "utf16" => self.utf16 = true,
+      "rest" => self.rest = true,
+      "terminator" => self.terminator = Some(parse_value(input)?),
+      "repeat" => self.repeat = Some(parse_value(input)?),
+      "max" => self.max = Some(parse_value(input)?),
+      "bit_order" => self.bit_order = Some(parse_value(input)?),
+      "bits" => self.bits = Some(parse_value(input)?),
+      "exact_size" => self.exact_size = true,
+      "fixed_point" => self.fixed_point = Some(parse_value(input)?),
+      _ => {
+        // Skip the arguments of attributes that are not ours.
+        input.parse::<proc_macro2::TokenStream>()?;
+        return Ok(false);
+      }
+    }
+    Ok(true)
+  }
+}
+
+/// Attributes that only apply to a struct or an enum as a whole.
+const CONTAINER_ONLY: [&str; 3] = ["size", "tag", "exact_size"];
+
+/// Returns the name of what the attribute sets. Attributes that set the same thing conflict.
+fn setting(name: &syn::Ident) -> String
+{
+  let name = name.to_string();
+  match name.as_str() {
+    "le" | "be" | "ne" | "little_endian" | "big_endian" | "native_endian" | "endian" => "endian".to_string(),
+    "reserved" => "pad".to_string(),
+    "count_bytes" => "count".to_string(),
+    _ => name,
+  }
+}
+
+/// Fails if an attribute that sets the same thing as `name` was already given.
+fn check_conflict(given: &[syn::Ident], name: &syn::Ident) -> syn::Result<()>
+{
+  match given.iter().find(|other| setting(other) == setting(name)) {
+    Some(other) if other == name => Err(syn::Error::new_spanned(
+      name,
+      format!("duplicate attribute `{}`", name),
+    )),
+    Some(other) if setting(name) == "endian" => Err(syn::Error::new_spanned(
+      name,
+      format!("conflicting endian attributes `{}` and `{}`", other, name),
+    )),
+    Some(other) => Err(syn::Error::new_spanned(
+      name,
+      format!("conflicting attributes `{}` and `{}`", other, name),
+    )),
+    None => Ok(()),
+  }
+}
+
+/// Parses the value of an attribute in the form of `#[name = value]` or `#[name(value)]`. Values
+/// that aren't literals (types, paths, expressions) can only be passed using the latter form.
+fn parse_value<T>(input: syn::parse::ParseStream) -> syn::Result<T>
+where
+  T: syn::parse::Parse,
+{
+  if input.peek(syn::Token![=]) {
+    input.parse::<syn::Token![=]>()?;
+    input.parse()
+  } else {
+    let content;
+    syn::parenthesized!(content in input);
+    content.parse()
+  }
+}
+
+/// Returns whether the type is a `PhantomData<T>`.
+fn is_phantom_type(ty: &syn::Type) -> bool
+{
+  match ty {
+    syn::Type::Path(syn::TypePath { qself: None, path }) => {
+      path.segments.last().is_some_and(|segment| segment.ident == "PhantomData")
+    }
+    _ => false,
+  }
+}
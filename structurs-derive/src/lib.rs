@@ -1,291 +1,2064 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned, ToTokens};
+use structurs_attributes::{Attributes, BitOrder, Endian, FixedPoint, Padding, PaddingSize};
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Read, attributes(le, be, ne, pad))]
-pub fn derive_read_struct(input: TokenStream) -> TokenStream
-{
-  derive_macro(input, true)
+/// Defines the entry points of the derive macros, which all accept the same attributes. Each one
+/// parses its input into `$ast` and turns the `syn::Result` of `$body` into the generated code.
+macro_rules! derive_macros {
+  ($($name:ident => fn $function:ident($ast:ident) $body:expr;)*) => {
+    $(
+      #[proc_macro_derive(
+        $name,
+        attributes(
+          structurs, le, be, ne, little_endian, big_endian, native_endian, pad, pad_before,
+          pad_after, skip, default, magic, assert_eq, length_prefix, count, count_bytes, cstring,
+          fixed, present_if, offset, align, read_with, write_with, varint, endian, tag, size, crc32,
+          digest, utf16, rest, terminator, repeat, max, bits, bit_order, exact_size, reserved,
+          fixed_point
+        )
+      )]
+      pub fn $function(input: TokenStream) -> TokenStream
+      {
+        let $ast = parse_macro_input!(input as DeriveInput);
+        let expanded: syn::Result<proc_macro2::TokenStream> = $body;
+        expanded.unwrap_or_else(syn::Error::into_compile_error).into()
+      }
+    )*
+  };
 }
 
-#[proc_macro_derive(Write, attributes(le, be, ne, pad))]
-pub fn derive_write_struct(input: TokenStream) -> TokenStream
-{
-  derive_macro(input, false)
+derive_macros! {
+  Read => fn derive_read_struct(ast) derive_macro(&ast, true, false);
+  Write => fn derive_write_struct(ast) derive_macro(&ast, false, false);
+  ReadWrite => fn derive_read_write_struct(ast) derive_macro(&ast, true, false).and_then(|read| {
+    let write = derive_macro(&ast, false, false)?;
+    Ok(quote! { #read #write })
+  });
+  AsyncRead => fn derive_async_read_struct(ast) derive_macro(&ast, true, true);
+  AsyncWrite => fn derive_async_write_struct(ast) derive_macro(&ast, false, true);
+  ByteSize => fn derive_byte_size(ast) derive_byte_size_macro(&ast);
+  StructursDefault => fn derive_default(ast) derive_default_macro(&ast);
 }
 
-/// Endian attribute value.
-enum Endian
+/// What the generated code does with the fields.
+#[derive(Clone, Copy)]
+struct Mode
 {
-  Little,
-  Big,
-  Native,
-  Normal,
+  read: bool,
+  /// Whether the fields are read from a source that can seek.
+  seek: bool,
+  /// Whether the fields are read or written asynchronously with tokio.
+  asynchronous: bool,
+  /// Whether the fields are read into an existing value, reusing the memory of its fields.
+  into: bool,
 }
 
-impl Default for Endian
+fn derive_macro(ast: &DeriveInput, read: bool, asynchronous: bool) -> syn::Result<proc_macro2::TokenStream>
 {
-  fn default() -> Self
-  {
-    Self::Normal
-  }
-}
+  let struct_name = &ast.ident;
 
-/// Padding attribute value.
-enum Padding
-{
-  Normal,
-  Bytes(usize),
-}
+  // Named, unnamed (tuple struct) and unit structs are supported. A unit struct doesn't have any
+  // fields, so it is read from and written as zero bytes. The fields of the variants of an enum
+  // are read and written the same way as the fields of a struct.
+  let fields: Vec<&syn::Field> = match &ast.data {
+    syn::Data::Struct(data) => data.fields.iter().collect(),
+    syn::Data::Enum(data) => data.variants.iter().flat_map(|variant| &variant.fields).collect(),
+    syn::Data::Union(_) => {
+      return Err(syn::Error::new_spanned(
+        struct_name,
+        format!(
+          "'{}' derive macro only supports structs and enums.",
+          match (read, asynchronous) {
+            (true, false) => "Read",
+            (false, false) => "Write",
+            (true, true) => "AsyncRead",
+            (false, true) => "AsyncWrite",
+          }
+        ),
+      ))
+    }
+  };
 
-impl Padding
-{
-  fn parse(attr: &syn::Attribute) -> Self
-  {
-    let mut padding = Padding::default();
-    if let Some(proc_macro2::TokenTree::Group(g)) = attr.tokens.clone().into_iter().next() {
-      let mut tokens = g.stream().into_iter();
-      match tokens.next() {
-        Some(proc_macro2::TokenTree::Ident(ref i)) => assert_eq!(i, "bytes"),
-        token => panic!("expected ident was 'bytes', but found: {:?}", token),
+  // Endian attribute of the struct is the default endian of its fields.
+  let struct_attrs = Attributes::new(&ast.attrs)?;
+
+  // Structs that have fields at an offset need to seek in the source, so they implement `ReadSeek`
+  // instead of `Read`.
+  let mut seek = false;
+  // Aligned fields need to know how many bytes are read or written before them, so the reader or
+  // the writer is wrapped in one that keeps track of it.
+  let mut aligned = false;
+  // Checksum fields need the CRC32 of the bytes before them, so the reader or the writer is wrapped
+  // in one that computes it.
+  let mut checksum = false;
+  // Digest fields need the hash of the bytes before them, so the reader or the writer is wrapped in
+  // one that feeds them to the hasher.
+  let mut hasher: Option<syn::Type> = None;
+  for f in &fields {
+    let attrs = Attributes::from_field(f)?;
+    check_owned(f, &attrs)?;
+    seek |= read && attrs.offset.is_some();
+    checksum |= attrs.crc32;
+    if let Some(digest) = attrs.digest {
+      match &hasher {
+        Some(other) if other.to_token_stream().to_string() != digest.to_token_stream().to_string() => {
+          return Err(syn::Error::new_spanned(
+            digest,
+            "'digest' fields of a struct must all use the same hasher.",
+          ));
+        }
+        _ => hasher = Some(digest),
       }
-      match tokens.next() {
-        Some(proc_macro2::TokenTree::Punct(ref p)) => assert_eq!(p.as_char(), '='),
-        token => panic!("expected punct was '=', but found: {:?}", token),
+    }
+    aligned |= attrs.align.is_some()
+      || [&attrs.padding, &attrs.pad_before, &attrs.pad_after]
+        .iter()
+        .any(|pad| Padding::is_aligned(pad));
+  }
+  let mode = Mode {
+    read,
+    seek,
+    asynchronous,
+    into: false,
+  };
+
+  // The bytes that a struct with an exact size was read from are counted by the reader that every
+  // synchronous read goes through.
+  let exact_size = read && struct_attrs.exact_size;
+  if struct_attrs.exact_size {
+    if let syn::Data::Enum(_) = &ast.data {
+      return Err(syn::Error::new_spanned(
+        struct_name,
+        "'exact_size' attribute can only be used on structs.",
+      ));
+    }
+    if exact_size && asynchronous {
+      return Err(syn::Error::new_spanned(
+        struct_name,
+        "'exact_size' attribute is not supported by the async derive macros.",
+      ));
+    }
+  }
+
+  // Type parameters need to implement the same trait as the struct to read or write the fields
+  // that use them. Fields that are not read or written with their own implementation don't need it.
+  let bound = match (read, seek, asynchronous) {
+    (true, _, true) => quote! { ::structurs::AsyncRead },
+    (true, true, false) => quote! { ::structurs::ReadSeek },
+    (true, false, false) => quote! { ::structurs::Read },
+    (false, _, true) => quote! { ::structurs::AsyncWrite },
+    (false, _, false) => quote! { ::structurs::Write },
+  };
+  let bounded_fields = fields
+    .iter()
+    .map(|f| {
+      let attrs = Attributes::from_field(f)?;
+      let custom = if read { attrs.read_with.is_some() } else { attrs.write_with.is_some() };
+      Ok(!(attrs.skip || attrs.padding.is_some() || custom))
+    })
+    .collect::<syn::Result<Vec<_>>>()?;
+  let used = fields.iter().zip(bounded_fields).filter(|(_, bounded)| *bounded).map(|(f, _)| &f.ty);
+  let mut generics = add_bounds(&ast.generics, used, bound);
+  // `#[size = N]` is checked by the `ByteSize` implementation, so the types it is used on need one.
+  // The size of a generic struct is checked when it is read or written.
+  if let Some(size) = &struct_attrs.size {
+    generics
+      .make_where_clause()
+      .predicates
+      .push(syn::parse_quote_spanned! { size.span()=> Self: ::structurs::ByteSize });
+  } else if exact_size {
+    generics
+      .make_where_clause()
+      .predicates
+      .push(syn::parse_quote! { Self: ::structurs::ByteSize });
+  }
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let check_size = if exact_size {
+    let name = struct_name.to_string();
+    quote! { ::structurs::private::check_exact_size(#name, __reader.count(), <Self as ::structurs::ByteSize>::SIZE)?; }
+  } else {
+    quote! {}
+  };
+
+  // Statements that read or write the fields, followed by the value that is returned.
+  let mut impl_fields = match &ast.data {
+    syn::Data::Struct(data) => {
+      let values: Vec<_> = field_members(&data.fields)
+        .iter()
+        .map(|member| quote! { self.#member })
+        .collect();
+      let mut statements = field_statements(&data.fields, None, &struct_attrs, &values, mode)?;
+      if read {
+        let construct = construct(quote! { Self }, &data.fields);
+        statements.push(quote! {
+          #check_size
+          Ok(#construct)
+        });
+      } else {
+        statements.push(quote! { Ok(()) });
       }
-      let length: usize = match tokens.next() {
-        Some(proc_macro2::TokenTree::Literal(l)) => match syn::Lit::new(l) {
-          syn::Lit::Int(lit_int) => lit_int.base10_parse().unwrap(),
-          lit => panic!("expected literal was of type integer, but found: {:?}", lit),
-        },
-        token => panic!("expected a literal, but found: {:?}", token),
-      };
-      padding = Padding::Bytes(length);
+      statements
+    }
+    syn::Data::Enum(data) => vec![enum_body(ast, data, &struct_attrs, mode)?],
+    syn::Data::Union(_) => unreachable!(),
+  };
+
+  // Structs that have fields whose memory can be reused are read into an existing value by reading
+  // these fields into the fields of the value. Other types are read into it as a whole.
+  let mut into_fields = Vec::new();
+  if let (syn::Data::Struct(data), true, false, false) = (&ast.data, read, seek, asynchronous) {
+    let mut reused = false;
+    for f in &fields {
+      let attrs = Attributes::from_field(f)?;
+      reused |= attrs.length_prefix.is_some()
+        || attrs.count.is_some()
+        || attrs.count_bytes.is_some()
+        || attrs.repeat.is_some();
+    }
+    if reused {
+      let values: Vec<_> = field_members(&data.fields)
+        .iter()
+        .map(|member| quote! { self.#member })
+        .collect();
+      let into_mode = Mode { into: true, ..mode };
+      into_fields = field_statements(&data.fields, None, &struct_attrs, &values, into_mode)?;
+      let construct = construct(quote! { Self }, &data.fields);
+      into_fields.push(quote! {
+        #check_size
+        *self = #construct;
+        Ok(())
+      });
     }
-    padding
   }
-}
 
-impl Default for Padding
-{
-  fn default() -> Self
-  {
-    Self::Normal
+  // Reading needs to know where each field starts for its errors, and nested structs need to know
+  // where they start in the stream to align their fields.
+  if aligned || !asynchronous {
+    let wrap = if read {
+      quote! {
+        let mut __reader = ::structurs::CountingReader::new(__reader);
+        let __reader = &mut __reader;
+      }
+    } else {
+      quote! {
+        let mut __writer = ::structurs::CountingWriter::new(__writer);
+        let __writer = &mut __writer;
+      }
+    };
+    impl_fields.insert(0, wrap.clone());
+    if !into_fields.is_empty() {
+      into_fields.insert(0, wrap);
+    }
   }
-}
 
-#[derive(Default)]
-struct Attributes
-{
-  endian: Endian,
-  padding: Option<Padding>,
-}
+  // The checksum wrapper goes under the counting one, which the fields need to be read through.
+  if checksum {
+    let wrap = if read {
+      quote! {
+        let mut __reader = ::structurs::private::Crc32Reader::new(__reader);
+        let __reader = &mut __reader;
+      }
+    } else {
+      quote! {
+        let mut __writer = ::structurs::private::Crc32Writer::new(__writer);
+        let __writer = &mut __writer;
+      }
+    };
+    impl_fields.insert(0, wrap.clone());
+    if !into_fields.is_empty() {
+      into_fields.insert(0, wrap);
+    }
+  }
 
-impl Attributes
-{
-  fn new(attrs: &Vec<syn::Attribute>) -> Self
-  {
-    let mut attributes = Self::default();
-    for attr in attrs {
-      for segment in &attr.path.segments {
-        if segment.ident == "le" {
-          attributes.endian = Endian::Little
-        } else if segment.ident == "be" {
-          attributes.endian = Endian::Big
-        } else if segment.ident == "ne" {
-          attributes.endian = Endian::Native
-        } else if segment.ident == "pad" {
-          attributes.padding = Some(Padding::parse(attr));
-        }
+  // The hashing wrapper goes under the checksum one, so both see the same bytes.
+  if let Some(hasher) = &hasher {
+    let wrap = if read {
+      quote! {
+        let mut __reader = ::structurs::HashingReader::new(__reader, <#hasher as ::core::default::Default>::default());
+        let __reader = &mut __reader;
       }
+    } else {
+      quote! {
+        let mut __writer = ::structurs::HashingWriter::new(__writer, <#hasher as ::core::default::Default>::default());
+        let __writer = &mut __writer;
+      }
+    };
+    impl_fields.insert(0, wrap.clone());
+    if !into_fields.is_empty() {
+      into_fields.insert(0, wrap);
     }
-    attributes
   }
-}
 
-enum ArrayLength
-{
-  Int(usize),
-  Const(syn::Expr),
-}
+  // Naming the size makes it checked when the code that reads or writes a generic struct is
+  // compiled, and the size of other structs is checked by a constant.
+  let mut size_check = quote! {};
+  if let Some(size) = &struct_attrs.size {
+    let check = quote_spanned! { size.span()=> let _ = <Self as ::structurs::ByteSize>::SIZE; };
+    impl_fields.insert(0, check.clone());
+    if !into_fields.is_empty() {
+      into_fields.insert(0, check);
+    }
+    if ast.generics.params.is_empty() {
+      size_check = quote_spanned! { size.span()=> const _: usize = <#struct_name as ::structurs::ByteSize>::SIZE; };
+    }
+  }
 
-fn derive_macro(input: TokenStream, read: bool) -> TokenStream
-{
-  let ast = parse_macro_input!(input as DeriveInput);
-  let struct_name = &ast.ident;
-  let generics = &ast.generics;
-  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let expanded = if read {
+    let read_into = if into_fields.is_empty() {
+      quote! {}
+    } else {
+      quote! {
+        fn read_into_endian<R>(&mut self, __reader: &mut R, __endian: ::structurs::Endian) -> ::structurs::io::Result<()>
+        where
+          R: ::structurs::io::Read + ?::core::marker::Sized
+        {
+          let __position: u64 = 0;
+          #(#into_fields)*
+        }
+      }
+    };
+    if asynchronous {
+      quote! {
+        impl #impl_generics ::structurs::AsyncRead for #struct_name #ty_generics #where_clause {
+          fn read_async<R>(
+            __reader: &mut R,
+          ) -> impl ::core::future::Future<Output = ::structurs::io::Result<Self>> + ::core::marker::Send
+          where
+            R: ::structurs::private::tokio::io::AsyncRead + ::core::marker::Unpin + ::core::marker::Send
+          {
+            Self::read_async_endian(__reader, ::structurs::Endian::Native)
+          }
+
+          async fn read_async_endian<R>(__reader: &mut R, __endian: ::structurs::Endian) -> ::structurs::io::Result<Self>
+          where
+            R: ::structurs::private::tokio::io::AsyncRead + ::core::marker::Unpin + ::core::marker::Send
+          {
+            #(#impl_fields)*
+          }
+        }
+      }
+    } else if seek {
+      quote! {
+        impl #impl_generics ::structurs::ReadSeek for #struct_name #ty_generics #where_clause {
+          fn read_seek<R>(__reader: &mut R) -> ::structurs::io::Result<Self>
+          where
+            R: ::structurs::io::Read + ::structurs::io::Seek + ?::core::marker::Sized
+          {
+            Self::read_seek_endian(__reader, ::structurs::Endian::Native)
+          }
+
+          fn read_seek_endian<R>(__reader: &mut R, __endian: ::structurs::Endian) -> ::structurs::io::Result<Self>
+          where
+            R: ::structurs::io::Read + ::structurs::io::Seek + ?::core::marker::Sized
+          {
+            let __position: u64 = 0;
+            #(#impl_fields)*
+          }
+        }
+      }
+    } else {
+      quote! {
+        impl #impl_generics ::structurs::Read for #struct_name #ty_generics #where_clause {
+          fn read<R>(__reader: &mut R) -> ::structurs::io::Result<Self>
+          where
+            R: ::structurs::io::Read + ?::core::marker::Sized
+          {
+            Self::read_endian(__reader, ::structurs::Endian::Native)
+          }
+
+          fn read_endian<R>(__reader: &mut R, __endian: ::structurs::Endian) -> ::structurs::io::Result<Self>
+          where
+            R: ::structurs::io::Read + ?::core::marker::Sized
+          {
+            Self::read_endian_at(__reader, __endian, 0)
+          }
+
+          fn read_endian_at<R>(
+            __reader: &mut R,
+            __endian: ::structurs::Endian,
+            __position: u64,
+          ) -> ::structurs::io::Result<Self>
+          where
+            R: ::structurs::io::Read + ?::core::marker::Sized
+          {
+            #(#impl_fields)*
+          }
 
-  // fields of the input struct must be named (at least for now).
-  let fields = if let syn::Data::Struct(syn::DataStruct {
-    fields: syn::Fields::Named(syn::FieldsNamed { ref named, .. }),
-    ..
-  }) = ast.data
-  {
-    named
+          #read_into
+        }
+      }
+    }
+  } else if asynchronous {
+    quote! {
+      impl #impl_generics ::structurs::AsyncWrite for #struct_name #ty_generics #where_clause {
+        fn write_async<W>(
+          &self,
+          __writer: &mut W,
+        ) -> impl ::core::future::Future<Output = ::structurs::io::Result<()>> + ::core::marker::Send
+        where
+          W: ::structurs::private::tokio::io::AsyncWrite + ::core::marker::Unpin + ::core::marker::Send
+        {
+          self.write_async_endian(__writer, ::structurs::Endian::Native)
+        }
+
+        async fn write_async_endian<W>(&self, __writer: &mut W, __endian: ::structurs::Endian) -> ::structurs::io::Result<()>
+        where
+          W: ::structurs::private::tokio::io::AsyncWrite + ::core::marker::Unpin + ::core::marker::Send
+        {
+          #(#impl_fields)*
+        }
+      }
+    }
   } else {
-    panic!(
-      "'{}' derive macro only supports structs with named fields.",
-      if read { "Read" } else { "Write" }
-    );
+    // Enums don't know the variant they are written as without matching on it, so they keep the
+    // default hint.
+    let size_hint = match &ast.data {
+      syn::Data::Struct(data) => {
+        let hint = size_hint(&data.fields, &struct_attrs)?;
+        quote! {
+          fn size_hint(&self) -> usize
+          {
+            #hint
+          }
+        }
+      }
+      _ => quote! {},
+    };
+    quote! {
+      impl #impl_generics ::structurs::Write for #struct_name #ty_generics #where_clause {
+        fn write<W>(&self, __writer: &mut W) -> ::structurs::io::Result<()>
+        where
+          W: ::structurs::io::Write + ?::core::marker::Sized
+        {
+          self.write_endian(__writer, ::structurs::Endian::Native)
+        }
+
+        fn write_endian<W>(&self, __writer: &mut W, __endian: ::structurs::Endian) -> ::structurs::io::Result<()>
+        where
+          W: ::structurs::io::Write + ?::core::marker::Sized
+        {
+          self.write_endian_at(__writer, __endian, 0)
+        }
+
+        fn write_endian_at<W>(
+          &self,
+          __writer: &mut W,
+          __endian: ::structurs::Endian,
+          __position: u64,
+        ) -> ::structurs::io::Result<()>
+        where
+          W: ::structurs::io::Write + ?::core::marker::Sized
+        {
+          #(#impl_fields)*
+        }
+
+        #size_hint
+      }
+    }
   };
 
-  // Fields to pass into struct construction block.
-  let impl_fields = fields.iter().map(|f| {
-    let field_name = &f.ident;
-    // `elem_ty` is the type of the element if the field type is an array, otherwise it is the type
-    // of the field. `elements` is the number of elements the array has and if it is not an array,
-    // then it is simply 1;
-    let (elem_ty, elements) = match array_type(&f.ty) {
-      Some(elems) => elems,
-      None => (&f.ty, ArrayLength::Int(1)),
-    };
+  Ok(quote! {
+    #expanded
+    #size_check
+  })
+}
 
-    // Read attributes passed to this field.
-    let attrs = Attributes::new(&f.attrs);
+/// Generates the statements that read or write the fields of a struct or of an enum variant one
+/// after another. Fields are read into locals named after them and written from `values`, the
+/// expressions that give their values.
+fn field_statements(
+  fields: &syn::Fields,
+  variant: Option<&syn::Ident>,
+  struct_attrs: &Attributes,
+  values: &[proc_macro2::TokenStream],
+  mode: Mode,
+) -> syn::Result<Vec<proc_macro2::TokenStream>>
+{
+  let Mode { read, asynchronous, .. } = mode;
+  let bindings = field_bindings(fields);
+  let members = field_members(fields);
+  let bit_fields = bit_fields(fields, struct_attrs)?;
+  fields
+    .iter()
+    .enumerate()
+    .map(|(index, f)| {
+      let field_name = &members[index];
+      let binding = &bindings[index];
+      let bit_field = &bit_fields[index];
+      // Read attributes passed to this field.
+      let attrs = Attributes::from_field(f)?;
+      if asynchronous {
+        if let Some(name) = attrs.blocking() {
+          return Err(syn::Error::new_spanned(
+            f,
+            format!("'{}' attribute is not supported by the async derive macros.", name),
+          ));
+        }
+      }
 
-    let func_token = get_func(elem_ty, &attrs.endian, field_name, read);
-    let func_body = get_body(&func_token, elem_ty, &elements);
+      let endian = match attrs.endian {
+        Endian::Normal => &struct_attrs.endian,
+        ref endian => endian,
+      };
+      // Arrays, including nested ones, are handled by the `Read` and `Write` implementations for
+      // arrays, so every field type is read and written as a whole.
+      let func_body = get_func(&f.ty, endian, &quote! { __value }, mode);
 
-    let default_func_token = quote! { <#elem_ty as ::std::default::Default>::default() };
-    let default_func_body = get_body(&default_func_token, elem_ty, &elements);
+      let default_func_body = default_value(f, &attrs);
 
-    let body = if let Some(pad) = attrs.padding {
-      match pad {
-        Padding::Normal => {
-          let elements_token = match &elements {
-            ArrayLength::Int(size) => quote! { #size },
-            ArrayLength::Const(expr) => quote! { #expr },
-          };
-          if read {
-            quote! { {
-              let mut pad_buf = [0u8; ::std::mem::size_of::<#elem_ty>() * #elements_token];
-              reader.read_exact(&mut pad_buf[..])?;
-              #default_func_body }
+      let body = if attrs.skip {
+        if read {
+          default_func_body.clone()
+        } else {
+          quote! {}
+        }
+      } else if let Some(BitField {
+        word,
+        backing,
+        shift,
+        width,
+        ..
+      }) = bit_field
+      {
+        let ty = &f.ty;
+        let mask = if *width == 128 {
+          quote! { u128::MAX }
+        } else {
+          proc_macro2::Literal::u128_unsuffixed((1u128 << width) - 1).into_token_stream()
+        };
+        if !read {
+          let field = binding.to_string();
+          quote! {
+            #word |= (::structurs::private::check_bits(#field, *__value as u128, #width)? as #backing) << #shift
+          }
+        } else if is_bool_type(ty) {
+          quote! { (#word >> #shift) & #mask != 0 }
+        } else {
+          quote! { ((#word >> #shift) & #mask) as #ty }
+        }
+      } else if let (true, Some(read_with)) = (read, &attrs.read_with) {
+        // The reader and writer may be unsized, so functions that expect sized ones are given a
+        // reference to them.
+        quote! { #read_with(&mut &mut *__reader)? }
+      } else if let (false, Some(write_with)) = (read, &attrs.write_with) {
+        quote! { #write_with(__value, &mut &mut *__writer)? }
+      } else if let Some(FixedPoint { ty, frac }) = &attrs.fixed_point {
+        let float_ty = &f.ty;
+        let is_float = matches!(
+          float_ty,
+          syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("f32") || path.is_ident("f64")
+        );
+        if !is_float {
+          return Err(syn::Error::new_spanned(
+            float_ty,
+            "'fixed_point' attribute can only be used on f32 and f64 fields.",
+          ));
+        }
+        // The integer is the number times 2^frac.
+        let scale = quote! { ((1u128 << #frac) as f64) };
+        if read {
+          let int_token = get_func(ty, endian, &quote! {}, mode);
+          quote! { ((#int_token as f64) / #scale) as #float_ty }
+        } else {
+          let field = binding.to_string();
+          let int_token = get_func(ty, endian, &quote! { __value }, mode);
+          quote! { {
+            let int = ::structurs::private::to_fixed_point(
+              #field,
+              *__value as f64 * #scale,
+              <#ty>::MIN as f64,
+              <#ty>::MAX as f64,
+            )? as #ty;
+            let __value = &int;
+            #int_token }
+          }
+        }
+      } else if attrs.varint {
+        let ty = &f.ty;
+        if read {
+          quote! { <#ty as ::structurs::Varint>::read_leb128(__reader)? }
+        } else {
+          quote! { ::structurs::Varint::write_leb128(__value, __writer)? }
+        }
+      } else if let Some(pad) = &attrs.padding {
+        let padding = padding_token(pad, &f.ty, mode);
+        if read {
+          quote! { {
+            #padding;
+            #default_func_body }
+          }
+        } else {
+          padding
+        }
+      } else if attrs.crc32 {
+        if !matches!(&f.ty, syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("u32")) {
+          return Err(syn::Error::new_spanned(f, "'crc32' attribute can only be used on u32 fields."));
+        }
+        // The CRC32 covers the bytes before the field, so it is taken before the field is read or
+        // written. The value of the field is ignored when it is written.
+        if read {
+          let field = binding.to_string();
+          quote! { {
+            let crc = ::structurs::private::Checksum::crc32(&*__reader);
+            let value: u32 = #func_body;
+            ::structurs::private::check_crc32(#field, value, crc)?;
+            value }
+          }
+        } else {
+          quote! { {
+            let __value = &::structurs::private::Checksum::crc32(&*__writer);
+            #func_body }
+          }
+        }
+      } else if attrs.digest.is_some() {
+        // Like the CRC32, the hash covers the bytes before the field.
+        let ty = &f.ty;
+        let hash = if read {
+          quote! { ::structurs::Finish::finish(::structurs::private::Hashing::hasher(&*__reader)) }
+        } else {
+          quote! { ::structurs::Finish::finish(::structurs::private::Hashing::hasher(&*__writer)) }
+        };
+        if read {
+          let field = binding.to_string();
+          quote! { {
+            let hash: #ty = ::core::convert::Into::into(#hash);
+            let value: #ty = #func_body;
+            ::structurs::private::check_digest(#field, &value, &hash)?;
+            value }
+          }
+        } else {
+          quote! { {
+            let hash: #ty = ::core::convert::Into::into(#hash);
+            let __value = &hash;
+            #func_body }
+          }
+        }
+      } else if let Some(magic) = &attrs.magic {
+        if read {
+          let field = binding.to_string();
+          let length = magic.value().len();
+          let read_token = read_exact(quote! { &mut magic }, mode);
+          quote! { {
+            let mut magic = [0u8; #length];
+            #read_token;
+            ::structurs::private::check_magic(#field, &magic, #magic)?;
+            magic }
+          }
+        } else {
+          write_all(quote! { #magic }, mode)
+        }
+      } else if attrs.rest {
+        if !matches!(vec_type(&f.ty), Some(syn::Type::Path(elem)) if elem.path.is_ident("u8")) {
+          return Err(syn::Error::new_spanned(&f.ty, "'rest' attribute can only be used on Vec<u8> fields."));
+        }
+        // Nothing can be read after the rest of the bytes, so only fields without bytes can follow.
+        for next in fields.iter().skip(index + 1) {
+          if !Attributes::from_field(next)?.skip {
+            return Err(syn::Error::new_spanned(
+              f,
+              "'rest' attribute can only be used on the last field.",
+            ));
+          }
+        }
+        if !read {
+          write_all(quote! { __value }, mode)
+        } else if asynchronous {
+          quote! { {
+            let mut bytes = ::structurs::private::Vec::new();
+            ::structurs::private::tokio::io::AsyncReadExt::read_to_end(__reader, &mut bytes).await?;
+            bytes }
+          }
+        } else {
+          quote! { ::structurs::private::read_to_end(__reader)? }
+        }
+      } else if attrs.cstring || attrs.terminator.is_some() {
+        let terminator = match &attrs.terminator {
+          Some(terminator) => quote! { (#terminator) },
+          None => quote! { 0 },
+        };
+        if read {
+          if is_string_type(&f.ty) {
+            quote! {
+              ::structurs::private::string_from_utf8(::structurs::private::read_terminated(__reader, #terminator)?)?
             }
           } else {
+            quote! { ::structurs::private::read_terminated(__reader, #terminator)? }
+          }
+        } else {
+          quote! {
+            ::structurs::private::write_terminated(
+              __writer,
+              ::core::convert::AsRef::<[u8]>::as_ref(__value),
+              #terminator,
+            )?
+          }
+        }
+      } else if let Some(width) = &attrs.fixed {
+        // A string is padded up to its width, while a `Vec<u8>` must have exactly that many bytes.
+        match (read, is_string_type(&f.ty)) {
+          (true, true) => quote! { ::structurs::private::read_fixed_string(__reader, #width)? },
+          (false, true) => quote! { ::structurs::private::write_fixed(__writer, __value.as_bytes(), #width)? },
+          (true, false) => quote! { ::structurs::private::read_fixed_bytes(__reader, #width)? },
+          (false, false) => {
+            let field = binding.to_string();
+            let write_token = write_all(quote! { __value }, mode);
             quote! { {
-              writer.write_all(&[0u8; ::std::mem::size_of::<$elem_ty>() * #elements_token]) }
+              ::structurs::private::check_length(#field, __value.len(), #width)?;
+              #write_token }
             }
           }
         }
-        Padding::Bytes(bytes) => {
+      } else if attrs.length_prefix.is_some()
+        || attrs.count.is_some()
+        || attrs.count_bytes.is_some()
+        || attrs.repeat.is_some()
+      {
+        // A `String` is stored as its UTF-8 bytes, so its length is the number of bytes. A
+        // `Cow<[T]>` or a `Cow<str>` is stored like a `Vec<T>` or a `String` and is read into its
+        // owned variant. A UTF-16 string is stored like a `Vec<u16>` of its code units. A map is
+        // stored as its keys and values one after another, and its length is the number of keys.
+        let cow = cow_type(&f.ty);
+        let map = map_types(&f.ty);
+        let is_string = is_string_type(&f.ty) || cow.is_some_and(is_str_type);
+        let utf16_ty: syn::Type = syn::parse_quote! { u16 };
+        let vec_elem_ty = if attrs.utf16 {
+          if !is_string {
+            return Err(syn::Error::new_spanned(&f.ty, "'utf16' attribute can only be used on String fields."));
+          }
+          Some(&utf16_ty)
+        } else if is_string || map.is_some() {
+          None
+        } else {
+          Some(vec_type(&f.ty).or_else(|| cow.and_then(slice_type)).ok_or_else(|| {
+            syn::Error::new_spanned(
+              &f.ty,
+              "'length_prefix', 'count', 'count_bytes' and 'repeat' attributes can only be used on Vec<T>, \
+               String, Cow, HashMap<K, V> and BTreeMap<K, V> fields.",
+            )
+          })?)
+        };
+        // The memory of a `Cow` can't be reused, since it might be borrowed.
+        let into = mode.into && cow.is_none() && map.is_none() && !attrs.utf16;
+        if let Some(count) = &attrs.count {
+          check_read_before("count", count, binding, &bindings[index..])?;
+        }
+        if let Some(count_bytes) = &attrs.count_bytes {
+          check_read_before("count_bytes", count_bytes, binding, &bindings[index..])?;
+        }
+        if read {
+          let length_token = match (&attrs.length_prefix, &attrs.count, &attrs.count_bytes, &attrs.repeat) {
+            (Some(length_prefix), ..) => get_func(&length_prefix.ty, length_prefix.endian(endian), &quote! {}, mode),
+            (None, Some(count), ..) => quote! { (#count) },
+            // The length of a UTF-8 string is its number of bytes already.
+            (None, None, Some(count_bytes), _) => {
+              let elem_size = match (vec_elem_ty, map) {
+                (Some(vec_elem_ty), _) => Some(quote! { <#vec_elem_ty as ::structurs::ByteSize>::SIZE }),
+                (None, Some((key_ty, value_ty))) => Some(quote! {
+                  <#key_ty as ::structurs::ByteSize>::SIZE + <#value_ty as ::structurs::ByteSize>::SIZE
+                }),
+                (None, None) => None,
+              };
+              match elem_size {
+                Some(elem_size) => {
+                  let field = binding.to_string();
+                  quote! {
+                    ::structurs::private::count_from_bytes(
+                      #field,
+                      ::structurs::private::length_to_usize(#count_bytes)?,
+                      #elem_size,
+                    )?
+                  }
+                }
+                None => quote! { (#count_bytes) },
+              }
+            }
+            (None, None, None, Some(repeat)) => quote! { (#repeat) },
+            (None, None, None, None) => unreachable!(),
+          };
+          let elems_token = match (vec_elem_ty, map) {
+            // Closures can't await, so the elements are read in a loop instead.
+            (Some(vec_elem_ty), _) if asynchronous => {
+              let elem_token = get_func(vec_elem_ty, endian, &quote! {}, mode);
+              quote! { {
+                let mut elems =
+                  ::structurs::private::Vec::with_capacity(::structurs::private::capacity::<#vec_elem_ty>(length));
+                for _ in 0..length {
+                  elems.push(#elem_token);
+                }
+                elems }
+              }
+            }
+            (Some(vec_elem_ty), _) => {
+              let elem_call = get_call(vec_elem_ty, endian, &quote! {}, mode);
+              if into {
+                let value = &values[index];
+                quote! { ::structurs::private::read_vec_into(::core::mem::take(&mut #value), length, |_| #elem_call)? }
+              } else {
+                quote! { ::structurs::private::read_vec(length, |_| #elem_call)? }
+              }
+            }
+            (None, Some((key_ty, value_ty))) if asynchronous => {
+              let key_token = get_func(key_ty, endian, &quote! {}, mode);
+              let value_token = get_func(value_ty, endian, &quote! {}, mode);
+              let map_ty = &f.ty;
+              quote! { {
+                let mut map = <#map_ty as ::core::default::Default>::default();
+                for _ in 0..length {
+                  let key = #key_token;
+                  ::core::iter::Extend::extend(&mut map, ::core::iter::once((key, #value_token)));
+                }
+                map }
+              }
+            }
+            (None, Some((key_ty, value_ty))) => {
+              let key_call = get_call(key_ty, endian, &quote! {}, mode);
+              let value_call = get_call(value_ty, endian, &quote! {}, mode);
+              quote! { ::structurs::private::read_map(length, || Ok((#key_call?, #value_call?)))? }
+            }
+            (None, None) if asynchronous => {
+              quote! {
+                ::structurs::private::string_from_utf8(::structurs::private::read_bytes_async(__reader, length).await?)?
+              }
+            }
+            (None, None) if into => {
+              let value = &values[index];
+              quote! { ::structurs::private::read_string_into(__reader, ::core::mem::take(&mut #value), length)? }
+            }
+            (None, None) => quote! { ::structurs::private::read_string(__reader, length)? },
+          };
+          let elems_token = if attrs.utf16 {
+            quote! { ::structurs::private::string_from_utf16(#elems_token)? }
+          } else {
+            elems_token
+          };
+          let elems_token = match cow {
+            Some(_) => quote! { ::structurs::private::Cow::Owned(#elems_token) },
+            None => elems_token,
+          };
+          let max_token = attrs.max.as_ref().map(|max| {
+            let field = binding.to_string();
+            quote! {
+              ::structurs::private::check_max(#field, length, #max, ::structurs::io::ErrorKind::InvalidData)?;
+            }
+          });
+          quote! { {
+            let length = ::structurs::private::length_to_usize(#length_token)?;
+            #max_token
+            #elems_token }
+          }
+        } else {
+          // The count of a `count` or a `count_bytes` field is written by the field it refers to. A
+          // `repeat` field must have the number of elements that is read back.
+          let length_token = match (&attrs.length_prefix, &attrs.repeat) {
+            (Some(length_prefix), _) => {
+              let length_ty = &length_prefix.ty;
+              let length_token = get_func(length_ty, length_prefix.endian(endian), &quote! { &length }, mode);
+              Some(quote! {
+                let length: #length_ty = ::structurs::private::length_from_usize(__value.len())?;
+                #length_token;
+              })
+            }
+            (None, Some(repeat)) => {
+              let field = binding.to_string();
+              Some(quote! {
+                ::structurs::private::check_length(#field, __value.len(), #repeat)?;
+              })
+            }
+            (None, None) => None,
+          };
+          let elems_token = match (vec_elem_ty, map) {
+            (Some(vec_elem_ty), _) => {
+              let elem_token = get_func(vec_elem_ty, endian, &quote! { elem }, mode);
+              quote! { for elem in __value.iter() { #elem_token; } }
+            }
+            (None, Some((key_ty, value_ty))) => {
+              let key_token = get_func(key_ty, endian, &quote! { key }, mode);
+              let value_token = get_func(value_ty, endian, &quote! { value }, mode);
+              quote! { for (key, value) in __value.iter() { #key_token; #value_token; } }
+            }
+            (None, None) => {
+              let write_token = write_all(quote! { __value.as_bytes() }, mode);
+              quote! { #write_token; }
+            }
+          };
+          let units_token = if attrs.utf16 {
+            Some(quote! {
+              let units: ::structurs::private::Vec<u16> = __value.encode_utf16().collect();
+              let __value = &units;
+            })
+          } else {
+            None
+          };
+          // A value with more elements than the maximum couldn't be read back.
+          let max_token = attrs.max.as_ref().map(|max| {
+            let field = binding.to_string();
+            quote! {
+              ::structurs::private::check_max(#field, __value.len(), #max, ::structurs::io::ErrorKind::InvalidInput)?;
+            }
+          });
+          quote! { {
+            #units_token
+            #max_token
+            #length_token
+            #elems_token }
+          }
+        }
+      } else if attrs.present_if.is_some() || (attrs.condition.is_some() && option_type(&f.ty).is_some()) {
+        let inner_ty = option_type(&f.ty).ok_or_else(|| {
+          syn::Error::new_spanned(&f.ty, "'present_if' attribute can only be used on Option<T> fields.")
+        })?;
+        if read {
+          let present = match (&attrs.present_if, &attrs.condition) {
+            (Some(present_if), _) => {
+              check_read_before("present_if", present_if, binding, &bindings[index..])?;
+              quote! { ::structurs::private::Flag::is_set(&(#present_if)) }
+            }
+            (None, Some(condition)) => {
+              check_read_before("if", condition, binding, &bindings[index..])?;
+              quote! { (#condition) }
+            }
+            (None, None) => unreachable!(),
+          };
+          let inner_token = get_func(inner_ty, endian, &quote! {}, mode);
+          quote! {
+            if #present { Some(#inner_token) } else { None }
+          }
+        } else {
+          // Whether the value is present is written by the fields the flag or the condition refer
+          // to.
+          let inner_token = get_func(inner_ty, endian, &quote! { value }, mode);
+          quote! {
+            if let Some(value) = __value { #inner_token; }
+          }
+        }
+      } else if attrs.utf16 {
+        return Err(syn::Error::new_spanned(
+          f,
+          "'utf16' attribute needs a 'length_prefix' or a 'count' attribute.",
+        ));
+      } else if attrs.max.is_some() {
+        return Err(syn::Error::new_spanned(
+          f,
+          "'max' attribute needs a 'length_prefix' or a 'count' attribute.",
+        ));
+      } else {
+        quote! { #func_body }
+      };
+
+      let body = match &attrs.align {
+        Some(align) if read => quote! { {
+          ::structurs::private::align_read(__reader, __position, #align, ::core::option::Option::None)?;
+          #body }
+        },
+        Some(align) => quote! { {
+          ::structurs::private::align_write(__writer, __position, #align, 0)?;
+          #body; }
+        },
+        None => body,
+      };
+
+      // Padding around the field belongs to it, so it is only read and written with the field.
+      let body = if attrs.pad_before.is_some() || attrs.pad_after.is_some() {
+        let before = attrs.pad_before.as_ref().map(|pad| {
+          let padding = padding_token(pad, &f.ty, mode);
+          quote! { #padding; }
+        });
+        let after = attrs.pad_after.as_ref().map(|pad| {
+          let padding = padding_token(pad, &f.ty, mode);
+          quote! { #padding; }
+        });
+        if read {
+          quote! { {
+            #before
+            let value = #body;
+            #after
+            value }
+          }
+        } else {
+          quote! { {
+            #before
+            #body;
+            #after }
+          }
+        }
+      } else {
+        body
+      };
+
+      let body = match &attrs.offset {
+        Some(offset) => {
+          if !read {
+            return Err(syn::Error::new_spanned(
+              offset,
+              "'offset' attribute is only supported by the 'Read' derive macro.",
+            ));
+          }
+          check_read_before("offset", offset, binding, &bindings[index..])?;
+          quote! { ::structurs::private::read_at(__reader, #offset, |__reader| Ok(#body))? }
+        }
+        None => body,
+      };
+
+      // Fields that are `Option<T>` are handled above, other fields get their default value when
+      // the condition is false.
+      let body = match &attrs.condition {
+        Some(condition) if option_type(&f.ty).is_none() => {
+          check_read_before("if", condition, binding, &bindings[index..])?;
           if read {
+            quote! { if #condition { #body } else { #default_func_body } }
+          } else {
+            let copies = copy_fields(condition, &bindings[..index], values);
             quote! { {
-              let mut pad_buf = [0u8; #bytes];
-              reader.read_exact(&mut pad_buf)?;
-              #default_func_body }
+              #copies
+              if #condition { #body; } }
             }
+          }
+        }
+        _ => body,
+      };
+
+      // The endian expression of a field is evaluated when it is written as well.
+      let body = match &attrs.endian {
+        Endian::Expr(expr) => {
+          check_read_before("endian", expr, binding, &bindings[index..])?;
+          if read {
+            body
           } else {
-            quote! {
-              writer.write_all(&[0u8; #bytes])
+            let copies = copy_fields(expr, &bindings[..index], values);
+            quote! { {
+              #copies
+              #body; }
             }
           }
         }
+        _ => body,
+      };
+
+      let body = match &attrs.assert_eq {
+        Some(expected) if read => {
+          let field = binding.to_string();
+          quote! { {
+            let value = #body;
+            ::structurs::private::check_eq(#field, &value, &(#expected))?;
+            value }
+          }
+        }
+        _ => body,
+      };
+
+      // Errors of reading a field tell which field failed and where it starts. Fields of enum
+      // variants are named after the variant as well.
+      let field = match field_name {
+        syn::Member::Named(ident) => ident.to_string(),
+        syn::Member::Unnamed(index) => index.index.to_string(),
+      };
+      let field = match variant {
+        Some(variant) => format!("{}.{}", variant, field),
+        None => field,
+      };
+      let read_statement = |binding: &syn::Ident, ty: &syn::Type, body: &proc_macro2::TokenStream| {
+        if asynchronous {
+          quote! {
+            let #binding: #ty = async {
+              let value: #ty = #body;
+              Ok::<_, ::structurs::io::Error>(value)
+            }
+            .await
+            .map_err(|err| ::structurs::private::field_error(#field, err))?;
+          }
+        } else {
+          quote! {
+            let #binding: #ty = ::structurs::private::read_field(__reader, #field, |__reader| {
+              let value: #ty = #body;
+              Ok(value)
+            })?;
+          }
+        }
+      };
+      // The integer that bit fields share is read before the first of them and written after the
+      // last of them, with the endian of the first of them.
+      let (word_before, word_after) = match bit_field {
+        Some(BitField {
+          word, backing, first, last, ..
+        }) => {
+          let before = match (first, read) {
+            (false, _) => None,
+            (true, true) => Some(read_statement(word, backing, &get_func(backing, endian, &quote! {}, mode))),
+            (true, false) => Some(quote! { let mut #word: #backing = 0; }),
+          };
+          let after = match (last, read) {
+            (true, false) => {
+              let write_token = get_func(backing, endian, &quote! { &#word }, mode);
+              Some(quote! { #write_token; })
+            }
+            _ => None,
+          };
+          (before, after)
+        }
+        None => (None, None),
+      };
+      let statement = if read {
+        read_statement(binding, &f.ty, &body)
+      } else {
+        let value = &values[index];
+        quote! { {
+          let __value = &#value;
+          #body; }
+        }
+      };
+      Ok(quote! {
+        #word_before
+        #statement
+        #word_after
+      })
+    })
+    .collect()
+}
+
+/// A field that takes some of the bits of an integer, which it shares with the fields around it.
+struct BitField
+{
+  /// Local that the integer is read into or written from.
+  word: syn::Ident,
+  /// Type of the integer.
+  backing: syn::Type,
+  /// Position of the lowest bit of the field in the integer.
+  shift: u32,
+  /// Number of bits of the field.
+  width: u32,
+  /// Whether the field is the first of the fields that share the integer.
+  first: bool,
+  /// Whether the field is the last of the fields that share the integer.
+  last: bool,
+}
+
+/// Groups consecutive fields with a `bits` attribute into the integers they share. The first field
+/// of a group takes the most significant bits, unless the group or the struct has
+/// `#[bit_order(lsb)]`. The bits of a group must add up to the size of an unsigned integer type.
+fn bit_fields(fields: &syn::Fields, struct_attrs: &Attributes) -> syn::Result<Vec<Option<BitField>>>
+{
+  let mut bit_fields: Vec<Option<BitField>> = Vec::new();
+  // Index of the first field, bit order and widths of the fields of the current group.
+  let mut group: Option<(usize, BitOrder, Vec<u32>)> = None;
+  for (index, f) in fields.iter().enumerate() {
+    let attrs = Attributes::from_field(f)?;
+    match (&attrs.bits, attrs.bit_order, &group) {
+      (None, Some(_), _) => {
+        return Err(syn::Error::new_spanned(f, "'bit_order' attribute needs a 'bits' attribute."));
       }
-    } else {
-      quote! { #func_body }
+      (Some(_), Some(_), Some(_)) => {
+        return Err(syn::Error::new_spanned(
+          f,
+          "'bit_order' attribute can only be used on the first field of a group of 'bits' fields.",
+        ));
+      }
+      _ => {}
+    }
+    let width = match &attrs.bits {
+      Some(bits) => {
+        let width: u32 = bits.base10_parse()?;
+        let type_bits = int_type_bits(&f.ty).ok_or_else(|| {
+          syn::Error::new_spanned(&f.ty, "'bits' attribute can only be used on unsigned integer and bool fields.")
+        })?;
+        if width == 0 || width > type_bits {
+          return Err(syn::Error::new_spanned(
+            bits,
+            format!("'bits' attribute needs between 1 and {} bits for this field.", type_bits),
+          ));
+        }
+        Some(width)
+      }
+      None => None,
     };
+    match (width, &mut group) {
+      (Some(width), Some((_, _, widths))) => widths.push(width),
+      (Some(width), None) => {
+        let order = attrs.bit_order.or(struct_attrs.bit_order).unwrap_or_default();
+        group = Some((index, order, vec![width]));
+      }
+      (None, _) => {
+        if let Some((start, order, widths)) = group.take() {
+          bit_fields.extend(bit_group(fields, start, order, &widths)?);
+        }
+        bit_fields.push(None);
+      }
+    }
+  }
+  if let Some((start, order, widths)) = group {
+    bit_fields.extend(bit_group(fields, start, order, &widths)?);
+  }
+  Ok(bit_fields)
+}
 
-    if read {
-      quote! { #field_name: #body }
-    } else {
-      quote! { #body }
+/// Lays out a group of bit fields that starts at field `start`, from the most significant bit of
+/// their integer to the least significant bit, or the other way around if `order` is `Lsb`.
+fn bit_group(
+  fields: &syn::Fields,
+  start: usize,
+  order: BitOrder,
+  widths: &[u32],
+) -> syn::Result<Vec<Option<BitField>>>
+{
+  let total: u32 = widths.iter().sum();
+  let backing = match total {
+    8 => quote! { u8 },
+    16 => quote! { u16 },
+    32 => quote! { u32 },
+    64 => quote! { u64 },
+    128 => quote! { u128 },
+    _ => {
+      let first = fields.iter().nth(start).unwrap();
+      let message = if total > 128 {
+        format!("'bits' fields have {} bits, which overflow the largest integer of 128 bits.", total)
+      } else {
+        format!(
+          "'bits' fields have {} bits, which is not the size of an integer. A field for the unused bits \
+           can fill them up to 8, 16, 32, 64 or 128 bits.",
+          total
+        )
+      };
+      return Err(syn::Error::new_spanned(first, message));
     }
-  });
+  };
+  let backing: syn::Type = syn::parse2(backing)?;
+  let word = quote::format_ident!("__bits_{}", start);
+  // Number of bits that come before the field in the order of the group.
+  let mut before = 0;
+  Ok(widths
+    .iter()
+    .enumerate()
+    .map(|(i, &width)| {
+      let shift = match order {
+        BitOrder::Msb => total - before - width,
+        BitOrder::Lsb => before,
+      };
+      before += width;
+      Some(BitField {
+        word: word.clone(),
+        backing: backing.clone(),
+        shift,
+        width,
+        first: i == 0,
+        last: i == widths.len() - 1,
+      })
+    })
+    .collect())
+}
 
-  let expanded = if read {
-    quote! {
-      impl #impl_generics ::structurs::Read for #struct_name #ty_generics #where_clause {
-        fn read<R>(reader: &mut R) -> ::std::io::Result<Self>
-        where
-          R: ::std::io::Read
-        {
-          Ok(Self {
-            #(#impl_fields,)*
-          })
+/// Returns whether the type is a number, `bool` or `char`, which are read directly from the reader.
+fn is_primitive_type(ty: &syn::Type) -> bool
+{
+  match ty {
+    syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident().is_some_and(|ident| {
+      [
+        "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "f32", "f64", "bool", "char",
+      ]
+      .iter()
+      .any(|primitive| ident == primitive)
+    }),
+    _ => false,
+  }
+}
+
+/// Returns the number of bits of the unsigned integer and bool types that bit fields can have.
+fn int_type_bits(ty: &syn::Type) -> Option<u32>
+{
+  let path = match ty {
+    syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+    _ => return None,
+  };
+  let ident = path.get_ident()?.to_string();
+  match ident.as_str() {
+    "bool" => Some(1),
+    "u8" => Some(8),
+    "u16" => Some(16),
+    "u32" => Some(32),
+    "u64" => Some(64),
+    "u128" => Some(128),
+    _ => None,
+  }
+}
+
+/// Returns whether the type is `bool`.
+fn is_bool_type(ty: &syn::Type) -> bool
+{
+  matches!(ty, syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("bool"))
+}
+
+/// Fields are read into local variables one after another, so the fields that are already read can
+/// be used by the attributes of the following fields. Fields of tuple structs are read into
+/// `field_0`, `field_1`, etc.
+fn field_bindings(fields: &syn::Fields) -> Vec<syn::Ident>
+{
+  fields
+    .iter()
+    .enumerate()
+    .map(|(index, f)| match &f.ident {
+      Some(ident) => ident.clone(),
+      None => quote::format_ident!("field_{}", index),
+    })
+    .collect()
+}
+
+/// Tuple struct fields don't have a name, so they are accessed by their position instead.
+fn field_members(fields: &syn::Fields) -> Vec<syn::Member>
+{
+  fields
+    .iter()
+    .enumerate()
+    .map(|(index, f)| match &f.ident {
+      Some(ident) => syn::Member::Named(ident.clone()),
+      None => syn::Member::Unnamed(syn::Index::from(index)),
+    })
+    .collect()
+}
+
+/// Returns the value of a field that isn't read, which is given by its `default` attribute or is
+/// the default of its type.
+fn default_value(f: &syn::Field, attrs: &Attributes) -> proc_macro2::TokenStream
+{
+  match &attrs.default {
+    Some(default) => quote! { #default },
+    // `Default` is only implemented for arrays of up to 32 elements, so arrays are built from the
+    // default of their element type instead.
+    None => match array_type(&f.ty) {
+      Some(elem_ty) => quote! { ::structurs::private::default_array::<#elem_ty, _>() },
+      None => {
+        let ty = &f.ty;
+        quote! { <#ty as ::core::default::Default>::default() }
+      }
+    },
+  }
+}
+
+/// Constructs the struct or the enum variant at `path` from the locals its fields are read into.
+fn construct(path: proc_macro2::TokenStream, fields: &syn::Fields) -> proc_macro2::TokenStream
+{
+  let bindings = field_bindings(fields);
+  let members = fields.iter().map(|f| &f.ident);
+  match fields {
+    syn::Fields::Named(_) => quote! { #path { #(#members: #bindings,)* } },
+    syn::Fields::Unnamed(_) => quote! { #path(#(#bindings,)*) },
+    syn::Fields::Unit => quote! { #path },
+  }
+}
+
+/// Generates the body that reads or writes an enum. The tag of the variant comes first, followed by
+/// the fields of the variant.
+fn enum_body(
+  ast: &DeriveInput,
+  data: &syn::DataEnum,
+  enum_attrs: &Attributes,
+  mode: Mode,
+) -> syn::Result<proc_macro2::TokenStream>
+{
+  let enum_name = ast.ident.to_string();
+  let tag_ty = tag_type(ast, enum_attrs)?;
+  if data.variants.is_empty() {
+    return Err(syn::Error::new_spanned(&ast.ident, "enums without variants can't be read or written."));
+  }
+
+  // Discriminants that are not given are one more than the discriminant of the previous variant,
+  // and the first one is zero.
+  let mut base = quote! { 0 };
+  let mut next = 0usize;
+  let mut arms = Vec::new();
+  for variant in &data.variants {
+    if let Some((_, expr)) = &variant.discriminant {
+      base = quote! { #expr };
+      next = 0;
+    }
+    let discriminant = match next {
+      0 => base.clone(),
+      _ => {
+        let next = proc_macro2::Literal::usize_unsuffixed(next);
+        quote! { (#base) + #next }
+      }
+    };
+    next += 1;
+
+    let ident = &variant.ident;
+    let path = quote! { Self::#ident };
+    let values: Vec<_> = field_bindings(&variant.fields)
+      .iter()
+      .map(|binding| quote! { *#binding })
+      .collect();
+    let statements = field_statements(&variant.fields, Some(ident), enum_attrs, &values, mode)?;
+    arms.push(if mode.read {
+      let construct = construct(path, &variant.fields);
+      quote! {
+        if __tag == #discriminant {
+          #(#statements)*
+          Ok(#construct)
+        }
+      }
+    } else {
+      let pattern = construct(path, &variant.fields);
+      let tag_token = get_func(&tag_ty, &enum_attrs.endian, &quote! { &__tag }, mode);
+      quote! {
+        #pattern => {
+          let __tag: #tag_ty = #discriminant;
+          #tag_token;
+          #(#statements)*
         }
       }
+    });
+  }
+
+  Ok(if mode.read {
+    let tag_token = get_func(&tag_ty, &enum_attrs.endian, &quote! {}, mode);
+    quote! {
+      let __tag: #tag_ty = #tag_token;
+      #(#arms else)* {
+        Err(::structurs::private::unknown_tag(#enum_name, __tag))
+      }
     }
   } else {
     quote! {
-      impl #impl_generics ::structurs::Write for #struct_name #ty_generics #where_clause {
-        fn write<W>(&self, writer: &mut W) -> ::std::io::Result<()>
-        where
-          W: ::std::io::Write
-        {
-          #(#impl_fields;)*
-          Ok(())
+      match self {
+        #(#arms)*
+      }
+      Ok(())
+    }
+  })
+}
+
+/// Returns the type of the tag of an enum, given by its `tag` attribute or by its `repr` attribute
+/// if it is an integer type.
+fn tag_type(ast: &DeriveInput, enum_attrs: &Attributes) -> syn::Result<syn::Type>
+{
+  if let Some(tag) = &enum_attrs.tag {
+    return Ok(tag.clone());
+  }
+  for attr in &ast.attrs {
+    if !attr.path.is_ident("repr") {
+      continue;
+    }
+    if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+      for nested in &list.nested {
+        if let syn::NestedMeta::Meta(syn::Meta::Path(path)) = nested {
+          let is_integer = ["u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128"]
+            .iter()
+            .any(|integer| path.is_ident(integer));
+          if is_integer {
+            return Ok(syn::Type::Path(syn::TypePath {
+              qself: None,
+              path: path.clone(),
+            }));
+          }
         }
       }
     }
+  }
+  Err(syn::Error::new_spanned(
+    &ast.ident,
+    "enums need a 'tag' attribute or a 'repr' attribute with an integer type.",
+  ))
+}
+
+fn derive_byte_size_macro(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream>
+{
+  let struct_name = &ast.ident;
+  let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+  let struct_attrs = Attributes::new(&ast.attrs)?;
+
+  let fields = match ast.data {
+    syn::Data::Struct(syn::DataStruct { ref fields, .. }) => fields,
+    // Enums whose variants don't have fields are stored as just their tag.
+    syn::Data::Enum(ref data) if data.variants.iter().all(|variant| variant.fields.is_empty()) => {
+      let tag_ty = tag_type(ast, &struct_attrs)?;
+      let (size, check) = assert_size(ast, &struct_attrs, quote! { <#tag_ty as ::structurs::ByteSize>::SIZE });
+      return Ok(quote! {
+        impl #impl_generics ::structurs::ByteSize for #struct_name #ty_generics #where_clause {
+          const SIZE: usize = #size;
+        }
+        #check
+      });
+    }
+    _ => {
+      return Err(syn::Error::new_spanned(
+        struct_name,
+        "'ByteSize' derive macro only supports structs and enums whose variants don't have fields.",
+      ))
+    }
   };
 
-  expanded.into()
+  // Number of bytes each field takes, and the bytes of padding before and after it.
+  let bits = bit_fields(fields, &struct_attrs)?;
+  let sizes = fields
+    .iter()
+    .zip(&bits)
+    .map(|(f, bits)| {
+      let attrs = Attributes::from_field(f)?;
+      check_owned(f, &attrs)?;
+      let ty = &f.ty;
+      let mut around = Vec::new();
+      for pad in [&attrs.pad_before, &attrs.pad_after] {
+        around.push(match pad {
+          None => quote! { 0 },
+          Some(Padding {
+            size: PaddingSize::Bytes(bytes),
+            ..
+          }) => quote! { #bytes },
+          Some(_) => {
+            return Err(syn::Error::new_spanned(
+              f,
+              "'ByteSize' derive macro only supports fields that always take the same number of bytes.",
+            ))
+          }
+        });
+      }
+      let size = if attrs.skip {
+        quote! { 0 }
+      } else if let Some(bits) = bits {
+        // The integer of a group of bit fields is counted once, at its first field.
+        let backing = &bits.backing;
+        if bits.first {
+          quote! { <#backing as ::structurs::ByteSize>::SIZE }
+        } else {
+          quote! { 0 }
+        }
+      } else if let Some(pad) = attrs.padding {
+        match pad.size {
+          PaddingSize::Normal => quote! { ::core::mem::size_of::<#ty>() },
+          PaddingSize::Bytes(bytes) => quote! { #bytes },
+          PaddingSize::Align(_) => {
+            return Err(syn::Error::new_spanned(
+              f,
+              "'ByteSize' derive macro only supports fields that always take the same number of bytes.",
+            ))
+          }
+        }
+      } else if let Some(width) = &attrs.fixed {
+        quote! { (#width) }
+      } else if let Some(FixedPoint { ty, .. }) = &attrs.fixed_point {
+        quote! { <#ty as ::structurs::ByteSize>::SIZE }
+      } else if let (Some(repeat), Some(elem_ty), None, None, None) =
+        (&attrs.repeat, vec_type(ty), &attrs.length_prefix, &attrs.count, &attrs.count_bytes)
+      {
+        quote! { (#repeat) * <#elem_ty as ::structurs::ByteSize>::SIZE }
+      } else if attrs.cstring
+        || attrs.terminator.is_some()
+        || attrs.length_prefix.is_some()
+        || attrs.count.is_some()
+        || attrs.count_bytes.is_some()
+        || attrs.present_if.is_some()
+        || attrs.condition.is_some()
+        || attrs.offset.is_some()
+        || attrs.align.is_some()
+        || attrs.read_with.is_some()
+        || attrs.write_with.is_some()
+        || attrs.varint
+        || attrs.utf16
+        || attrs.rest
+      {
+        return Err(syn::Error::new_spanned(
+          f,
+          "'ByteSize' derive macro only supports fields that always take the same number of bytes.",
+        ));
+      } else {
+        quote! { <#ty as ::structurs::ByteSize>::SIZE }
+      };
+      Ok((around[0].clone(), size, around[1].clone(), attrs.skip))
+    })
+    .collect::<syn::Result<Vec<_>>>()?;
+
+  // Where each field that has bytes starts, after the fields and the padding before it.
+  let totals: Vec<_> = sizes
+    .iter()
+    .map(|(before, size, after, _)| quote! { (#before + #size + #after) })
+    .collect();
+  let layout: Vec<_> = field_members(fields)
+    .iter()
+    .zip(&sizes)
+    .enumerate()
+    .filter(|(_, (_, (_, _, _, skip)))| !skip)
+    .map(|(index, (member, (before, size, _, _)))| {
+      let name = match member {
+        syn::Member::Named(ident) => ident.to_string(),
+        syn::Member::Unnamed(index) => index.index.to_string(),
+      };
+      // Bit fields are listed with the bytes of the integer they share.
+      if let Some(field_bits) = &bits[index] {
+        let start = (0..=index)
+          .rev()
+          .find(|&i| matches!(&bits[i], Some(b) if b.first))
+          .unwrap_or(index);
+        let previous = &totals[..start];
+        let before = &sizes[start].0;
+        let backing = &field_bits.backing;
+        return quote! { (#name, 0 #(+ #previous)* + #before, <#backing as ::structurs::ByteSize>::SIZE) };
+      }
+      let previous = &totals[..index];
+      quote! { (#name, 0 #(+ #previous)* + #before, #size) }
+    })
+    .collect();
+
+  // Type parameters of the fields whose type gives their size need to implement `ByteSize`.
+  let mut used = Vec::new();
+  for f in fields {
+    let attrs = Attributes::from_field(f)?;
+    if !(attrs.skip || attrs.padding.is_some() || attrs.fixed.is_some()) {
+      used.push(&f.ty);
+    }
+  }
+  let generics = add_bounds(&ast.generics, used, quote! { ::structurs::ByteSize });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  let (size, check) = assert_size(ast, &struct_attrs, quote! { 0 #(+ #totals)* });
+
+  Ok(quote! {
+    impl #impl_generics ::structurs::ByteSize for #struct_name #ty_generics #where_clause {
+      const SIZE: usize = #size;
+      const LAYOUT: &'static [(&'static str, usize, usize)] = &[#(#layout),*];
+    }
+    #check
+  })
+}
+
+fn derive_default_macro(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream>
+{
+  let struct_name = &ast.ident;
+  let fields = match &ast.data {
+    syn::Data::Struct(data) => &data.fields,
+    _ => {
+      return Err(syn::Error::new_spanned(
+        struct_name,
+        "'StructursDefault' derive macro only supports structs.",
+      ))
+    }
+  };
+
+  // Every field gets the value that reading would give it if it weren't read. Like when it is read,
+  // the value can use the fields before it.
+  let bindings = field_bindings(fields);
+  let mut values = Vec::new();
+  let mut used = Vec::new();
+  for (f, binding) in fields.iter().zip(&bindings) {
+    let attrs = Attributes::from_field(f)?;
+    if attrs.default.is_none() {
+      used.push(array_type(&f.ty).unwrap_or(&f.ty));
+    }
+    let value = default_value(f, &attrs);
+    values.push(quote! { let #binding = #value; });
+  }
+  let generics = add_bounds(&ast.generics, used, quote! { ::core::default::Default });
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+  let construct = construct(quote! { Self }, fields);
+
+  Ok(quote! {
+    impl #impl_generics ::core::default::Default for #struct_name #ty_generics #where_clause {
+      fn default() -> Self
+      {
+        #(#values)*
+        #construct
+      }
+    }
+  })
+}
+
+/// Wraps the expression that gives the size of a struct so that it fails to compile if the size
+/// isn't the one given by `#[size = N]`. The size of a generic struct is checked for each of its
+/// types that `SIZE` is used with, the size of other structs is checked whenever they are compiled
+/// by the returned constant.
+fn assert_size(
+  ast: &DeriveInput,
+  attrs: &Attributes,
+  size: proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, Option<proc_macro2::TokenStream>)
+{
+  let expected = match &attrs.size {
+    Some(expected) => expected,
+    None => return (size, None),
+  };
+  let struct_name = &ast.ident;
+  let message = format!(
+    "the size of '{}' is not the {} bytes given by its `size` attribute",
+    struct_name,
+    quote! { #expected }
+  );
+  let check = if ast.generics.params.is_empty() {
+    Some(quote! {
+      const _: usize = <#struct_name as ::structurs::ByteSize>::SIZE;
+    })
+  } else {
+    None
+  };
+  let size = quote! { {
+    let size: usize = #size;
+    ::core::assert!(size == #expected, #message);
+    size }
+  };
+  (size, check)
+}
+
+/// Generates the expression that gives the `size_hint` of a struct that is written, the sum of the
+/// bytes of its fields. Fields whose number of bytes is only known when they are written don't add
+/// any. The size of a struct with `#[size = N]` is known already.
+fn size_hint(fields: &syn::Fields, struct_attrs: &Attributes) -> syn::Result<proc_macro2::TokenStream>
+{
+  if struct_attrs.size.is_some() {
+    return Ok(quote! { <Self as ::structurs::ByteSize>::SIZE });
+  }
+  let members = field_members(fields);
+  let bits = bit_fields(fields, struct_attrs)?;
+  let mut sizes = Vec::new();
+  for ((f, member), bits) in fields.iter().zip(&members).zip(&bits) {
+    let attrs = Attributes::from_field(f)?;
+    let ty = &f.ty;
+    for pad in [&attrs.pad_before, &attrs.pad_after] {
+      if let Some(Padding {
+        size: PaddingSize::Bytes(bytes),
+        ..
+      }) = pad
+      {
+        sizes.push(quote! { (#bytes) });
+      }
+    }
+    let size = if attrs.skip {
+      None
+    } else if let Some(bits) = bits {
+      // The integer of a group of bit fields is counted once, at its first field.
+      let backing = &bits.backing;
+      Some(quote! { <#backing as ::structurs::ByteSize>::SIZE }).filter(|_| bits.first)
+    } else if let Some(pad) = &attrs.padding {
+      match &pad.size {
+        PaddingSize::Normal => Some(quote! { ::core::mem::size_of::<#ty>() }),
+        PaddingSize::Bytes(bytes) => Some(quote! { (#bytes) }),
+        PaddingSize::Align(_) => None,
+      }
+    } else if let Some(width) = &attrs.fixed {
+      Some(quote! { (#width) })
+    } else if let Some(FixedPoint { ty, .. }) = &attrs.fixed_point {
+      Some(quote! { <#ty as ::structurs::ByteSize>::SIZE })
+    } else if let (Some(_), Some(elem_ty), None, None, None) =
+      (&attrs.repeat, vec_type(ty), &attrs.length_prefix, &attrs.count, &attrs.count_bytes)
+    {
+      Some(quote! { self.#member.iter().map(<#elem_ty as ::structurs::Write>::size_hint).sum::<usize>() })
+    } else if attrs.cstring
+      || attrs.terminator.is_some()
+      || attrs.length_prefix.is_some()
+      || attrs.count.is_some()
+      || attrs.count_bytes.is_some()
+      || attrs.repeat.is_some()
+      || attrs.present_if.is_some()
+      || attrs.condition.is_some()
+      || attrs.offset.is_some()
+      || attrs.align.is_some()
+      || attrs.read_with.is_some()
+      || attrs.write_with.is_some()
+      || attrs.varint
+      || attrs.utf16
+      || attrs.rest
+    {
+      None
+    } else {
+      Some(quote! { ::structurs::Write::size_hint(&self.#member) })
+    };
+    sizes.extend(size);
+  }
+  Ok(quote! { 0 #(+ #sizes)* })
 }
 
 fn get_func(
   ty: &syn::Type,
   endian: &Endian,
-  field_name: &Option<proc_macro2::Ident>,
-  read: bool,
+  value: &proc_macro2::TokenStream,
+  mode: Mode,
+) -> proc_macro2::TokenStream
+{
+  let call = get_call(ty, endian, value, mode);
+  quote! { #call? }
+}
+
+/// Returns the call that reads or writes a value of the type, without propagating its error.
+fn get_call(
+  ty: &syn::Type,
+  endian: &Endian,
+  value: &proc_macro2::TokenStream,
+  mode: Mode,
 ) -> proc_macro2::TokenStream
 {
-  if read {
-    match endian {
-      Endian::Little => quote! { <#ty as ::structurs::PrimitiveRead>::read_le(reader)? },
-      Endian::Big => quote! { <#ty as ::structurs::PrimitiveRead>::read_be(reader)? },
-      Endian::Native => quote! { <#ty as ::structurs::PrimitiveRead>::read_ne(reader)? },
-      Endian::Normal => quote! { <#ty as ::structurs::Read>::read(reader)? },
+  // `ReadSeek` is only implemented for arrays whose elements implement `Read`, so arrays of structs
+  // that seek are read element by element.
+  if let (Mode { read: true, seek: true, asynchronous: false, .. }, Some(elem_ty)) = (mode, array_type(ty)) {
+    let elem_call = get_call(elem_ty, endian, value, mode);
+    return quote! { ::structurs::private::read_array(|_| #elem_call) };
+  }
+
+  // Fields without an endian attribute use the endian the struct itself is read or written with.
+  let endian = match endian {
+    Endian::Little => quote! { ::structurs::Endian::Little },
+    Endian::Big => quote! { ::structurs::Endian::Big },
+    Endian::Native => quote! { ::structurs::Endian::Native },
+    Endian::Expr(expr) => quote! { (#expr) },
+    Endian::Normal => quote! { __endian },
+  };
+  match mode {
+    Mode { read: true, asynchronous: true, .. } => {
+      quote! { <#ty as ::structurs::AsyncRead>::read_async_endian(__reader, #endian).await }
+    }
+    Mode { read: false, asynchronous: true, .. } => {
+      quote! { <#ty as ::structurs::AsyncWrite>::write_async_endian(#value, __writer, #endian).await }
+    }
+    Mode { read: true, seek: true, .. } => {
+      quote! { <#ty as ::structurs::ReadSeek>::read_seek_endian(__reader, #endian) }
+    }
+    // Data types that aren't primitive are read through a trait object, so their reading code isn't
+    // generated again for the reader of every struct they are nested in. The reader may be unsized,
+    // so the trait object is made from a reference to it. They are given their position in the
+    // stream, which nested structs align their fields from.
+    Mode { read: true, .. } if !is_primitive_type(ty) && array_type(ty).is_none() => {
+      quote! { ({
+        let __start = __position + __reader.count();
+        <#ty as ::structurs::Read>::read_dyn_at(&mut &mut *__reader, #endian, __start)
+      }) }
     }
+    Mode { read: true, .. } => quote! { <#ty as ::structurs::Read>::read_endian(__reader, #endian) },
+    Mode { read: false, .. } if !is_primitive_type(ty) && array_type(ty).is_none() => {
+      quote! { ({
+        let __start = __position + __writer.count();
+        <#ty as ::structurs::Write>::write_endian_at(#value, __writer, #endian, __start)
+      }) }
+    }
+    Mode { read: false, .. } => quote! { <#ty as ::structurs::Write>::write_endian(#value, __writer, #endian) },
+  }
+}
+
+/// Fills the buffer with bytes from the reader.
+fn read_exact(buf: proc_macro2::TokenStream, mode: Mode) -> proc_macro2::TokenStream
+{
+  if mode.asynchronous {
+    quote! { ::structurs::private::tokio::io::AsyncReadExt::read_exact(__reader, #buf).await? }
+  } else {
+    quote! { ::structurs::io::Read::read_exact(__reader, #buf)? }
+  }
+}
+
+/// Reads and discards the bytes of a padding, or writes them as zeros. `ty` is the type of the
+/// field whose size is the size of a padding without arguments.
+fn padding_token(pad: &Padding, ty: &syn::Type, mode: Mode) -> proc_macro2::TokenStream
+{
+  let fill = match &pad.fill {
+    Some(fill) => quote! { (#fill) },
+    None => quote! { 0 },
+  };
+  // Only strict padding checks the bytes that are read.
+  let check = if pad.strict {
+    quote! { ::core::option::Option::Some(#fill) }
   } else {
-    match endian {
-      Endian::Little => quote! { <#ty as ::structurs::PrimitiveWrite>::write_le(&self.#field_name, writer)? },
-      Endian::Big => quote! { <#ty as ::structurs::PrimitiveWrite>::write_be(&self.#field_name, writer)? },
-      Endian::Native => quote! { <#ty as ::structurs::PrimitiveWrite>::write_ne(&self.#field_name, writer)? },
-      Endian::Normal => quote! { <#ty as ::structurs::Write>::write(&self.#field_name, writer)? },
-    }
-  }
-}
-
-fn array_type(ty: &syn::Type) -> Option<(&syn::Type, ArrayLength)>
-{
-  if let syn::Type::Array(syn::TypeArray { elem, len, .. }) = ty {
-    Some((
-      elem,
-      match len {
-        syn::Expr::Lit(syn::ExprLit {
-          lit: syn::Lit::Int(len),
-          ..
-        }) => ArrayLength::Int(len.base10_parse().unwrap_or_else(|err| {
-          panic!("a parsing error occurred while reading the length of an array: {}", err);
-        })),
-        _ => ArrayLength::Const(len.clone()),
-      },
-    ))
+    quote! { ::core::option::Option::None }
+  };
+  // The size of the type can depend on the generic parameters of the struct, so it can't be the
+  // length of an array.
+  let length = match &pad.size {
+    PaddingSize::Normal => quote! { ::core::mem::size_of::<#ty>() },
+    PaddingSize::Bytes(bytes) => quote! { #bytes },
+    PaddingSize::Align(align) if mode.read => {
+      return quote! { ::structurs::private::align_read(__reader, __position, #align, #check)? };
+    }
+    PaddingSize::Align(align) => {
+      return quote! { ::structurs::private::align_write(__writer, __position, #align, #fill)? };
+    }
+  };
+  match (mode.read, mode.asynchronous) {
+    (true, true) => quote! { ::structurs::private::read_padding_async(__reader, #length, #check).await? },
+    (true, false) => quote! { ::structurs::private::read_padding(__reader, #length, #check)? },
+    (false, true) => quote! { ::structurs::private::write_padding_async(__writer, #length, #fill).await? },
+    (false, false) => quote! { ::structurs::private::write_padding(__writer, #length, #fill)? },
+  }
+}
+
+/// Writes all of the bytes to the writer.
+fn write_all(bytes: proc_macro2::TokenStream, mode: Mode) -> proc_macro2::TokenStream
+{
+  if mode.asynchronous {
+    quote! { ::structurs::private::tokio::io::AsyncWriteExt::write_all(__writer, #bytes).await? }
   } else {
-    None
+    quote! { ::structurs::io::Write::write_all(__writer, #bytes)? }
   }
 }
 
-fn get_body(token: &proc_macro2::TokenStream, elem_ty: &syn::Type, ty_length: &ArrayLength)
-  -> proc_macro2::TokenStream
+/// Returns the element type of an array.
+fn array_type(ty: &syn::Type) -> Option<&syn::Type>
 {
-  match ty_length {
-    ArrayLength::Int(size) => {
-      let tokens: Vec<proc_macro2::TokenStream> = (0..size.clone()).map(|_| token.clone()).collect();
-      if tokens.len() == 1 {
-        quote! { #(#tokens)* }
-      } else {
-        quote! { [ #(#tokens,)* ] }
-      }
+  match ty {
+    syn::Type::Array(syn::TypeArray { elem, .. }) => Some(elem),
+    _ => None,
+  }
+}
+
+/// Fails if the expression of an attribute refers to the field itself or to a field after it, since
+/// those are not read yet.
+fn check_read_before(attr: &str, expr: &syn::Expr, binding: &syn::Ident, unread: &[syn::Ident]) -> syn::Result<()>
+{
+  if let syn::Expr::Path(syn::ExprPath { path, .. }) = expr {
+    if let Some(field) = unread.iter().find(|&unread| path.is_ident(unread)) {
+      return Err(syn::Error::new_spanned(
+        path,
+        format!(
+          "'{}' attribute of field '{}' refers to field '{}' which isn't read before it.",
+          attr, binding, field
+        ),
+      ));
     }
-    ArrayLength::Const(expr) => quote! { {
-      let mut body: [#elem_ty; #expr] = [0; #expr];
-      for i in 0..#expr {
-        body[i] = #token;
+  }
+  Ok(())
+}
+
+/// Copies the fields that are used in the expression into locals with their names, like they are
+/// when reading, so the expression can be evaluated when writing as well.
+fn copy_fields(
+  expr: &syn::Expr,
+  bindings: &[syn::Ident],
+  values: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream
+{
+  let mut used = Vec::new();
+  referenced_idents(quote! { #expr }, bindings, &mut used);
+  let used_bindings = used.iter().map(|&i| &bindings[i]);
+  let used_values = used.iter().map(|&i| &values[i]);
+  quote! { #(let #used_bindings = #used_values;)* }
+}
+
+/// Collects the indices of the identifiers, e.g. the names of fields, that are used in the tokens.
+fn referenced_idents(tokens: proc_macro2::TokenStream, idents: &[syn::Ident], used: &mut Vec<usize>)
+{
+  for token in tokens {
+    match token {
+      proc_macro2::TokenTree::Ident(ident) => {
+        if let Some(i) = idents.iter().position(|known| *known == ident) {
+          if !used.contains(&i) {
+            used.push(i);
+          }
+        }
       }
-      body }
-    },
+      proc_macro2::TokenTree::Group(group) => referenced_idents(group.stream(), idents, used),
+      _ => {}
+    }
+  }
+}
+
+/// Adds `bound` to the type parameters that are used by the types, so the bounds that the fields of
+/// these types need don't have to be written by hand.
+fn add_bounds<'a, I>(generics: &syn::Generics, types: I, bound: proc_macro2::TokenStream) -> syn::Generics
+where
+  I: IntoIterator<Item = &'a syn::Type>,
+{
+  let params: Vec<syn::Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+  let mut used = Vec::new();
+  for ty in types {
+    referenced_idents(quote! { #ty }, &params, &mut used);
+  }
+  let mut generics = generics.clone();
+  if used.is_empty() {
+    return generics;
+  }
+  let where_clause = generics.make_where_clause();
+  for i in used {
+    let param = &params[i];
+    where_clause.predicates.push(syn::parse_quote! { #param: #bound });
+  }
+  generics
+}
+
+/// Returns whether the type is a `String`.
+fn is_string_type(ty: &syn::Type) -> bool
+{
+  match ty {
+    syn::Type::Path(syn::TypePath { qself: None, path }) => path
+      .segments
+      .last()
+      .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_empty()),
+    _ => false,
+  }
+}
+
+/// Returns whether the type is a `str`.
+fn is_str_type(ty: &syn::Type) -> bool
+{
+  match ty {
+    syn::Type::Path(syn::TypePath { qself: None, path }) => path.is_ident("str"),
+    _ => false,
+  }
+}
+
+/// Checks that a field that has bytes doesn't borrow. A borrowed value can't be read from a source,
+/// so structs with lifetimes can only use them in fields without bytes, like `PhantomData<&'a T>`.
+fn check_owned(field: &syn::Field, attrs: &Attributes) -> syn::Result<()>
+{
+  match &field.ty {
+    syn::Type::Reference(_) if !attrs.skip => Err(syn::Error::new_spanned(
+      &field.ty,
+      "fields that borrow can't be read or written, use an owned type like Vec<T> or Cow<'a, T> instead.",
+    )),
+    _ => Ok(()),
+  }
+}
+
+/// Returns the element type of a slice `[T]`.
+fn slice_type(ty: &syn::Type) -> Option<&syn::Type>
+{
+  match ty {
+    syn::Type::Slice(slice) => Some(&slice.elem),
+    _ => None,
+  }
+}
+
+/// Returns the borrowed type of a `Cow<'a, B>`, e.g. `[u8]` or `str`.
+fn cow_type(ty: &syn::Type) -> Option<&syn::Type>
+{
+  generic_argument(ty, "Cow")
+}
+
+/// Returns the inner type of an `Option<T>`.
+fn option_type(ty: &syn::Type) -> Option<&syn::Type>
+{
+  generic_argument(ty, "Option")
+}
+
+/// Returns the element type of a `Vec<T>`.
+fn vec_type(ty: &syn::Type) -> Option<&syn::Type>
+{
+  generic_argument(ty, "Vec")
+}
+
+/// Returns the key and value types of a `HashMap<K, V>` or a `BTreeMap<K, V>`.
+fn map_types(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)>
+{
+  if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+    let segment = path.segments.last()?;
+    if segment.ident != "HashMap" && segment.ident != "BTreeMap" {
+      return None;
+    }
+    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+      let mut types = args.args.iter().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(arg_ty) => Some(arg_ty),
+        _ => None,
+      });
+      return Some((types.next()?, types.next()?));
+    }
+  }
+  None
+}
+
+/// Returns the type argument of a generic type with the given name, e.g. `T` of `Vec<T>`. Lifetime
+/// arguments are skipped.
+fn generic_argument<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type>
+{
+  if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+    let segment = path.segments.last()?;
+    if segment.ident != name {
+      return None;
+    }
+    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+      return args.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(arg_ty) => Some(arg_ty),
+        _ => None,
+      });
+    }
   }
+  None
 }
@@ -0,0 +1,56 @@
+//! This crate only exists to check that structurs and the code generated by its derive macros
+//! compile without `std`. Build it on its own with `cargo build -p structurs-no-std-test`, so the
+//! `std` feature of structurs is not enabled by the other packages of the workspace.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use structurs::io;
+use structurs::{Reader, Writer};
+
+#[derive(structurs::Read, structurs::Write, structurs::ByteSize, Debug, PartialEq)]
+#[be]
+pub struct Header
+{
+  #[magic = b"HD"]
+  pub magic: [u8; 2],
+  pub version: u16,
+  #[le]
+  pub flags: u32,
+  #[pad(bytes = 2)]
+  pub reserved: structurs::Pad,
+  pub table: [[u8; 2]; 2],
+}
+
+#[derive(structurs::Read, structurs::Write, Debug, PartialEq)]
+pub struct Record
+{
+  pub header: Header,
+  #[length_prefix(u8)]
+  pub name: String,
+  #[count(2)]
+  pub values: Vec<i16>,
+  #[varint]
+  pub id: u64,
+  #[cstring]
+  pub comment: String,
+  #[structurs(if = header.version >= 2)]
+  pub extra: Option<u8>,
+  #[align = 4]
+  pub end: u8,
+}
+
+pub fn read_record(mut bytes: &[u8]) -> io::Result<Record>
+{
+  bytes.read_as::<Record>()
+}
+
+pub fn write_record(record: &Record) -> io::Result<Vec<u8>>
+{
+  let mut bytes = Vec::new();
+  bytes.write_as(record)?;
+  Ok(bytes)
+}
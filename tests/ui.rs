@@ -0,0 +1,7 @@
+/// Checks the errors that invalid attributes are reported with.
+#[test]
+fn ui()
+{
+  let t = trybuild::TestCases::new();
+  t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  #[count(entry_count)]
+  entries: Vec<u8>,
+  entry_count: u8,
+}
+
+fn main() {}
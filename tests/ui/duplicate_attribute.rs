@@ -0,0 +1,9 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  count: u8,
+  #[structurs(count(count), count(count))]
+  entries: Vec<u8>,
+}
+
+fn main() {}
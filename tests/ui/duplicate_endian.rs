@@ -0,0 +1,9 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  #[le]
+  #[structurs(le)]
+  value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  #[length_prefix(8)]
+  entries: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  #[length_prefix(u8)]
+  entries: [u8; 4],
+}
+
+fn main() {}
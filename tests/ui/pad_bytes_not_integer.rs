@@ -0,0 +1,8 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  #[pad(bytes = "4")]
+  pad_field: structurs::Pad,
+}
+
+fn main() {}
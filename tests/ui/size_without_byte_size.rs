@@ -0,0 +1,10 @@
+#[derive(structurs::Read, structurs::Write)]
+#[size = 8]
+struct Entry
+{
+  id: u32,
+  #[length_prefix(u8)]
+  name: String,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+#[derive(structurs::Read, structurs::Write)]
+struct Size
+{
+  #[size = 7]
+  a: u8,
+  b: u32,
+}
+
+#[derive(structurs::Read, structurs::Write)]
+struct Tag
+{
+  #[tag(u8)]
+  a: u8,
+  b: u32,
+}
+
+#[derive(structurs::Read)]
+struct ExactSize
+{
+  #[structurs(exact_size)]
+  a: u8,
+  b: u32,
+}
+
+fn main() {}
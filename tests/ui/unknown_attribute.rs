@@ -0,0 +1,8 @@
+#[derive(structurs::Read)]
+struct Test
+{
+  #[structurs(le, lenght_prefix(u8))]
+  entries: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[derive(structurs::Read, structurs::Write, structurs::ByteSize)]
+#[size = 8]
+struct Entry
+{
+  id: u32,
+  flags: u16,
+}
+
+fn main() {}